@@ -0,0 +1,501 @@
+//! Leitura e escrita do AFD (Arquivo Fonte de Dados) definido pela Portaria 671/2021.
+//!
+//! Cada linha é um registro de largura fixa identificado pelo tipo na posição 10.
+//! Registros dos tipos 1 a 5 terminam com um CRC-16 (KERMIT) em hexadecimal; o
+//! tipo 7 traz um hash SHA-256 encadeado, que é apenas preservado na leitura.
+//! Marcações no leiaute antigo da Portaria 1510 (tipo 3 com PIS) também são aceitas.
+
+use chrono::{DateTime, FixedOffset, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use tauri::AppHandle;
+
+use crate::history::{self, PunchSource};
+
+const LAYOUT_VERSION: &str = "003";
+const AFD_DATETIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%z";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AfdHeader {
+    pub employer_document: String,
+    pub employer_name: String,
+    pub rep_number: String,
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AfdRecord {
+    Header(AfdHeader),
+    CompanyChange { nsr: u64, recorded_at: NaiveDateTime, employer_document: String, employer_name: String },
+    /// Marcação de ponto: CPF no leiaute 671, PIS no leiaute 1510.
+    Punch { nsr: u64, at: NaiveDateTime, employee: String },
+    ClockAdjustment { nsr: u64, before: NaiveDateTime, after: NaiveDateTime },
+    Employee { nsr: u64, recorded_at: NaiveDateTime, operation: String, cpf: String, name: String },
+    Event { nsr: u64, at: NaiveDateTime, code: String },
+    RepPunch { nsr: u64, at: NaiveDateTime, cpf: String, online: bool, hash: String },
+    Trailer { punches: u64 },
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AfdLineError {
+    pub line: usize,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct AfdFile {
+    pub records: Vec<AfdRecord>,
+    pub errors: Vec<AfdLineError>,
+}
+
+/// CRC-16/KERMIT (polinômio 0x1021 refletido, valor inicial zero).
+fn crc16(data: &[u8]) -> u16 {
+    let mut crc: u16 = 0;
+    for &byte in data {
+        crc ^= byte as u16;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0x8408 } else { crc >> 1 };
+        }
+    }
+    crc
+}
+
+/// O AFD é gravado em ISO-8859-1; caracteres fora dessa tabela viram `?`.
+fn to_latin1(text: &str) -> Vec<u8> {
+    text.chars()
+        .map(|c| if (c as u32) <= 0xFF { c as u8 } else { b'?' })
+        .collect()
+}
+
+fn from_latin1(bytes: Vec<u8>) -> String {
+    String::from_utf8(bytes).unwrap_or_else(|e| e.into_bytes().iter().map(|&b| b as char).collect())
+}
+
+fn crc_hex(body: &str) -> String {
+    format!("{:04X}", crc16(&to_latin1(body)))
+}
+
+fn digits(value: &str) -> String {
+    value.chars().filter(char::is_ascii_digit).collect()
+}
+
+/// Compara CPFs/PIS ignorando pontuação e zeros à esquerda.
+fn same_document(a: &str, b: &str) -> bool {
+    let (a, b) = (digits(a), digits(b));
+    !a.is_empty() && a.trim_start_matches('0') == b.trim_start_matches('0')
+}
+
+struct Line<'a> {
+    chars: &'a [char],
+}
+
+impl Line<'_> {
+    /// Campo na posição `start` (base 1, como nas tabelas da portaria).
+    fn field(&self, start: usize, len: usize) -> String {
+        self.chars.iter().skip(start - 1).take(len).collect::<String>().trim().to_string()
+    }
+
+    fn nsr(&self) -> Result<u64, String> {
+        self.field(1, 9).parse().map_err(|_| "NSR inválido".to_string())
+    }
+
+    fn datetime(&self, start: usize) -> Result<NaiveDateTime, String> {
+        let value = self.field(start, 24);
+        DateTime::<FixedOffset>::parse_from_str(&value, AFD_DATETIME_FORMAT)
+            .map(|dt| dt.naive_local())
+            .map_err(|_| format!("Data/hora inválida: {}", value))
+    }
+
+    fn date(&self, start: usize) -> Option<NaiveDate> {
+        NaiveDate::parse_from_str(&self.field(start, 10), "%Y-%m-%d").ok()
+    }
+
+    fn check_crc(&self) -> Result<(), String> {
+        let len = self.chars.len();
+        let body: String = self.chars[..len - 4].iter().collect();
+        let expected = crc_hex(&body);
+        let found = self.field(len - 3, 4).to_uppercase();
+        if expected == found {
+            Ok(())
+        } else {
+            Err(format!("CRC inválido (esperado {}, encontrado {})", expected, found))
+        }
+    }
+}
+
+fn parse_line(chars: &[char]) -> Result<AfdRecord, String> {
+    let line = Line { chars };
+    let record_type = line.field(10, 1);
+
+    if line.field(1, 9) == "999999999" {
+        return Ok(AfdRecord::Trailer {
+            punches: line.field(19, 9).parse().unwrap_or_default(),
+        });
+    }
+
+    // Cabeçalho e marcação no leiaute da Portaria 1510, sem CRC
+    let legacy = matches!((record_type.as_str(), chars.len()), ("1", 232) | ("3", 34));
+
+    let expected_len = match record_type.as_str() {
+        _ if legacy => chars.len(),
+        "1" => 302,
+        "2" => 331,
+        "3" => 50,
+        "4" => 73,
+        "5" => 118,
+        "6" => 36,
+        "7" => 137,
+        other => return Err(format!("Tipo de registro desconhecido: {}", other)),
+    };
+    if chars.len() != expected_len {
+        return Err(format!(
+            "Registro tipo {} deve ter {} caracteres, encontrados {}",
+            record_type,
+            expected_len,
+            chars.len()
+        ));
+    }
+    if !legacy && matches!(record_type.as_str(), "1" | "2" | "3" | "4" | "5") {
+        line.check_crc()?;
+    }
+
+    let record = match (record_type.as_str(), legacy) {
+        ("1", true) => AfdRecord::Header(AfdHeader {
+            employer_document: line.field(12, 14),
+            employer_name: line.field(38, 150),
+            rep_number: line.field(188, 17),
+            start_date: NaiveDate::parse_from_str(&line.field(205, 8), "%d%m%Y").ok(),
+            end_date: NaiveDate::parse_from_str(&line.field(213, 8), "%d%m%Y").ok(),
+        }),
+        ("1", _) => AfdRecord::Header(AfdHeader {
+            employer_document: line.field(12, 14),
+            employer_name: line.field(40, 150),
+            rep_number: line.field(190, 17),
+            start_date: line.date(207),
+            end_date: line.date(217),
+        }),
+        ("2", _) => AfdRecord::CompanyChange {
+            nsr: line.nsr()?,
+            recorded_at: line.datetime(11)?,
+            employer_document: line.field(50, 14),
+            employer_name: line.field(78, 150),
+        },
+        ("3", true) => {
+            let value = format!("{}{}", line.field(11, 8), line.field(19, 4));
+            AfdRecord::Punch {
+                nsr: line.nsr()?,
+                at: NaiveDateTime::parse_from_str(&value, "%d%m%Y%H%M")
+                    .map_err(|_| format!("Data/hora inválida: {}", value))?,
+                employee: line.field(23, 12),
+            }
+        }
+        ("3", _) => AfdRecord::Punch {
+            nsr: line.nsr()?,
+            at: line.datetime(11)?,
+            employee: line.field(35, 12),
+        },
+        ("4", _) => AfdRecord::ClockAdjustment {
+            nsr: line.nsr()?,
+            before: line.datetime(11)?,
+            after: line.datetime(35)?,
+        },
+        ("5", _) => AfdRecord::Employee {
+            nsr: line.nsr()?,
+            recorded_at: line.datetime(11)?,
+            operation: line.field(35, 1),
+            cpf: line.field(36, 12),
+            name: line.field(48, 52),
+        },
+        ("6", _) => AfdRecord::Event {
+            nsr: line.nsr()?,
+            at: line.datetime(11)?,
+            code: line.field(35, 2),
+        },
+        _ => AfdRecord::RepPunch {
+            nsr: line.nsr()?,
+            at: line.datetime(11)?,
+            cpf: line.field(35, 12),
+            online: line.field(73, 1) == "0",
+            hash: line.field(74, 64),
+        },
+    };
+
+    Ok(record)
+}
+
+/// Interpreta o conteúdo de um AFD. Linhas inválidas são registradas em
+/// `errors` e não interrompem a leitura do restante do arquivo.
+pub fn parse_afd(content: &str) -> AfdFile {
+    let mut file = AfdFile::default();
+
+    for (index, raw_line) in content.lines().enumerate() {
+        let raw_line = raw_line.trim_end_matches(['\r', '\u{1a}']);
+        if raw_line.trim().is_empty() {
+            continue;
+        }
+
+        let chars: Vec<char> = raw_line.chars().collect();
+        // A assinatura digital do REP-P fica na última linha e não é um registro
+        if chars.len() == 100 && !chars.iter().take(10).all(char::is_ascii_digit) {
+            continue;
+        }
+
+        match parse_line(&chars) {
+            Ok(record) => file.records.push(record),
+            Err(message) => file.errors.push(AfdLineError { line: index + 1, message }),
+        }
+    }
+
+    file
+}
+
+fn numeric(value: &str, len: usize) -> String {
+    let value = digits(value);
+    format!("{:0>len$}", &value[value.len().saturating_sub(len)..], len = len)
+}
+
+fn text(value: &str, len: usize) -> String {
+    let truncated: String = value.chars().take(len).collect();
+    format!("{:<len$}", truncated, len = len)
+}
+
+fn with_crc(body: String) -> String {
+    let crc = crc_hex(&body);
+    body + &crc
+}
+
+fn afd_datetime(at: NaiveDateTime) -> String {
+    Local
+        .from_local_datetime(&at)
+        .earliest()
+        .map(|dt| dt.format("%Y-%m-%dT%H:%M:00%z").to_string())
+        .unwrap_or_else(|| format!("{}-0300", at.format("%Y-%m-%dT%H:%M:00")))
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct AfdExportOptions {
+    /// CNPJ (14 dígitos) ou CPF (11 dígitos) do empregador.
+    pub employer_document: String,
+    pub employer_name: String,
+    pub employee_cpf: String,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+}
+
+/// Gera um AFD com cabeçalho, uma marcação (tipo 3) por registro do período e trailer.
+pub fn write_afd(options: &AfdExportOptions, punches: &[NaiveDateTime]) -> String {
+    let employer_digits = digits(&options.employer_document);
+    let employer_type = if employer_digits.len() == 11 { "2" } else { "1" };
+
+    let header = with_crc(format!(
+        "{}1{}{}{}{}{}{}{}{}{}{}{}{}",
+        "0".repeat(9),
+        employer_type,
+        numeric(&employer_digits, 14),
+        numeric("", 14),
+        text(&options.employer_name, 150),
+        numeric("", 17),
+        options.start_date.format("%Y-%m-%d"),
+        options.end_date.format("%Y-%m-%d"),
+        afd_datetime(Local::now().naive_local()),
+        LAYOUT_VERSION,
+        "1",
+        numeric("", 14),
+        text("NoPonto", 30),
+    ));
+
+    let mut lines = vec![header];
+
+    for (index, at) in punches.iter().enumerate() {
+        lines.push(with_crc(format!(
+            "{}3{}{}",
+            numeric(&(index + 1).to_string(), 9),
+            afd_datetime(*at),
+            numeric(&options.employee_cpf, 12),
+        )));
+    }
+
+    // Quantidade de registros dos tipos 2 a 7
+    lines.push(format!(
+        "999999999{}{}{}{}{}{}9",
+        numeric("0", 9),
+        numeric(&punches.len().to_string(), 9),
+        numeric("0", 9),
+        numeric("0", 9),
+        numeric("0", 9),
+        numeric("0", 9),
+    ));
+
+    lines.join("\r\n") + "\r\n"
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AfdImportSummary {
+    pub header: Option<AfdHeader>,
+    pub total_records: usize,
+    pub matched_punches: usize,
+    pub imported_days: usize,
+    pub skipped_days: Vec<NaiveDate>,
+    pub errors: Vec<AfdLineError>,
+}
+
+/// Importa para o histórico local as marcações (tipos 3 e 7) do empregado
+/// identificado por `employee_document` (CPF ou PIS).
+#[tauri::command]
+pub async fn import_afd(
+    app: AppHandle,
+    path: String,
+    employee_document: String,
+    overwrite: bool,
+) -> Result<AfdImportSummary, String> {
+    let bytes = std::fs::read(&path)
+        .map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    let afd = parse_afd(&from_latin1(bytes));
+
+    let mut days: BTreeMap<NaiveDate, Vec<NaiveTime>> = BTreeMap::new();
+    let mut header = None;
+    for record in &afd.records {
+        match record {
+            AfdRecord::Header(h) => header = Some(h.clone()),
+            AfdRecord::Punch { at, employee, .. } if same_document(employee, &employee_document) => {
+                days.entry(at.date()).or_default().push(at.time());
+            }
+            AfdRecord::RepPunch { at, cpf, .. } if same_document(cpf, &employee_document) => {
+                days.entry(at.date()).or_default().push(at.time());
+            }
+            _ => {}
+        }
+    }
+
+    let mut history = history::load_history(&app)?;
+    let mut skipped_days = Vec::new();
+    let mut imported_days = 0;
+
+    for (date, times) in &mut days {
+        times.sort();
+        let existing = history.times(*date);
+        if !overwrite && !existing.is_empty() && existing != *times {
+            skipped_days.push(*date);
+            continue;
+        }
+        history.set_day(*date, times, PunchSource::Afd);
+        imported_days += 1;
+    }
+
+    history::save_history(&app, &history)?;
+    if let Err(e) = crate::ics::refresh_feed_from_state(&app) {
+        println!("Failed to update calendar feed: {}", e);
+    }
+
+    println!("AFD import: {} records, {} days imported, {} errors", afd.records.len(), imported_days, afd.errors.len());
+
+    Ok(AfdImportSummary {
+        header,
+        total_records: afd.records.len(),
+        matched_punches: days.values().map(Vec::len).sum(),
+        imported_days,
+        skipped_days,
+        errors: afd.errors,
+    })
+}
+
+/// Exporta as marcações do histórico local no formato AFD para auditoria.
+#[tauri::command]
+pub async fn export_afd(app: AppHandle, path: String, options: AfdExportOptions) -> Result<usize, String> {
+    let history = history::load_history(&app)?;
+
+    let punches: Vec<NaiveDateTime> = history
+        .range(options.start_date, options.end_date)
+        .flat_map(|(date, punches)| punches.iter().map(move |punch| date.and_time(punch.time)))
+        .collect();
+
+    std::fs::write(&path, to_latin1(&write_afd(&options, &punches)))
+        .map_err(|e| format!("Failed to write {}: {}", path, e))?;
+
+    println!("Exported {} punches to AFD {}", punches.len(), path);
+    Ok(punches.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options() -> AfdExportOptions {
+        AfdExportOptions {
+            employer_document: "12.345.678/0001-90".to_string(),
+            employer_name: "Empresa Exemplo Ltda".to_string(),
+            employee_cpf: "123.456.789-09".to_string(),
+            start_date: NaiveDate::from_ymd_opt(2026, 3, 2).unwrap(),
+            end_date: NaiveDate::from_ymd_opt(2026, 3, 2).unwrap(),
+        }
+    }
+
+    fn punches() -> Vec<NaiveDateTime> {
+        ["08:00", "12:00", "13:00", "17:00"]
+            .iter()
+            .map(|time| NaiveDate::from_ymd_opt(2026, 3, 2).unwrap().and_time(NaiveTime::parse_from_str(time, "%H:%M").unwrap()))
+            .collect()
+    }
+
+    #[test]
+    fn crc16_matches_kermit_check_value() {
+        assert_eq!(crc16(b"123456789"), 0x2189);
+        assert_eq!(crc_hex("123456789"), "2189");
+    }
+
+    #[test]
+    fn writes_fixed_width_records() {
+        let afd = write_afd(&options(), &punches());
+        let lines: Vec<&str> = afd.split("\r\n").filter(|line| !line.is_empty()).collect();
+
+        assert_eq!(lines.len(), 6);
+        assert_eq!(lines[0].chars().count(), 302);
+        for line in &lines[1..5] {
+            assert_eq!(line.chars().count(), 50);
+        }
+    }
+
+    #[test]
+    fn trailer_counts_record_types_2_to_7() {
+        let afd = write_afd(&options(), &punches());
+        let trailer = afd.lines().last().unwrap();
+
+        assert_eq!(trailer.len(), 64);
+        assert_eq!(trailer, format!("999999999{}{}{}9", "0".repeat(9), "000000004", "0".repeat(36)));
+    }
+
+    #[test]
+    fn parses_written_file() {
+        let afd = parse_afd(&write_afd(&options(), &punches()));
+
+        assert!(afd.errors.is_empty(), "{:?}", afd.errors);
+        let parsed: Vec<NaiveDateTime> = afd
+            .records
+            .iter()
+            .filter_map(|record| match record {
+                AfdRecord::Punch { at, employee, .. } if same_document(employee, "12345678909") => Some(*at),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(parsed, punches());
+        assert!(matches!(afd.records.last(), Some(AfdRecord::Trailer { punches: 4 })));
+    }
+
+    #[test]
+    fn checks_record_length_and_crc() {
+        let before = afd_datetime(punches()[0]);
+        let after = afd_datetime(punches()[1]);
+        let adjustment = with_crc(format!("0000000054{}{}{}", before, after, numeric("12345678909", 11)));
+        assert_eq!(adjustment.chars().count(), 73);
+        assert!(matches!(parse_line(&adjustment.chars().collect::<Vec<_>>()), Ok(AfdRecord::ClockAdjustment { nsr: 5, .. })));
+
+        let short: Vec<char> = adjustment[..72].chars().collect();
+        assert!(parse_line(&short).is_err());
+
+        let mut corrupted: Vec<char> = adjustment.chars().collect();
+        corrupted[20] = if corrupted[20] == '1' { '2' } else { '1' };
+        assert!(parse_line(&corrupted).unwrap_err().starts_with("CRC inválido"));
+    }
+}
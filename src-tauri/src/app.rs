@@ -0,0 +1,1081 @@
+use tauri::{
+    Manager, RunEvent, WindowEvent, State, AppHandle, Emitter, WebviewWindowBuilder,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use tokio::time::{sleep, Duration};
+use chrono::{DateTime, Local, NaiveDate, NaiveTime, TimeZone};
+use tauri_plugin_store::StoreExt;
+
+#[cfg(desktop)]
+use crate::autostart;
+#[cfg(target_os = "linux")]
+use crate::dbus;
+use crate::{
+    afd, chat, control, email, events, export, history, holidays, http_api, ics, mqtt, punch_import, push, report,
+    schedule, tray, webhooks,
+};
+use crate::{encrypt_data, parse_work_times, plan_workday, read_pontomais_config, TimeData, WorkStatus, WARNING_MINUTES};
+use history::PunchSource;
+use schedule::Reminder;
+use events::WorkEvent;
+use crate::providers::{provider_from_config, ProviderInfo, TimeCardProvider, AVAILABLE_PROVIDERS};
+
+pub(crate) type SharedState = Arc<Mutex<Option<WorkStatus>>>;
+
+#[tauri::command]
+fn greet(name: &str) -> String {
+    format!("Hello, {}! You've been greeted from Rust!", name)
+}
+
+/// Controle do monitoramento em andamento: tarefa em segundo plano e
+/// silenciamento temporário das notificações.
+#[derive(Default)]
+pub(crate) struct MonitorControl {
+    pub(crate) task: Mutex<Option<tokio::task::JoinHandle<()>>>,
+    pub(crate) snoozed_until: Mutex<Option<DateTime<Local>>>,
+    /// Horários do monitoramento em andamento, salvos ao encerrar o app.
+    pub(crate) session: Mutex<Option<ActiveSession>>,
+}
+
+pub(crate) const ACTIVE_SESSION_KEY: &str = "active_session";
+
+/// Monitoramento ativo no momento em que o app foi encerrado.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct ActiveSession {
+    pub(crate) date: NaiveDate,
+    #[serde(flatten)]
+    pub(crate) times: TimeData,
+}
+
+impl MonitorControl {
+    pub(crate) fn notifications_snoozed(&self) -> bool {
+        self.snoozed_until.lock().unwrap().is_some_and(|until| Local::now() < until)
+    }
+}
+
+#[tauri::command]
+async fn start_work_monitoring(
+    app: AppHandle,
+    state: State<'_, SharedState>,
+    inicio1: String,
+    fim1: String,
+    inicio2: String,
+) -> Result<(), String> {
+    begin_work_monitoring(&app, state.inner(), &inicio1, &fim1, &inicio2).await
+}
+
+const LAST_START_NOTICE_KEY: &str = "last_start_notice";
+
+/// Whether the start of the day still needs to be announced. Resuming the
+/// same day (app restart, tray toggle) doesn't post again unless the expected
+/// end changed.
+fn should_announce_start(app: &AppHandle, today: NaiveDate, end_time: &str) -> bool {
+    let Ok(store) = app.store("noponto.dat") else { return false };
+    let notice = serde_json::json!({ "date": today, "end_time": end_time });
+    if store.get(LAST_START_NOTICE_KEY).as_ref() == Some(&notice) {
+        return false;
+    }
+    store.set(LAST_START_NOTICE_KEY, notice);
+    let _ = store.save();
+    true
+}
+
+/// Starts (or restarts) the monitor. Shared by the command and the tray menu.
+pub(crate) async fn begin_work_monitoring(
+    app: &AppHandle,
+    state: &SharedState,
+    inicio1: &str,
+    fim1: &str,
+    inicio2: &str,
+) -> Result<(), String> {
+    println!("Starting work monitoring with times: {} {} {}", inicio1, fim1, inicio2);
+    
+    let [start1, end1, start2] = parse_work_times(inicio1, fim1, inicio2)?;
+    let today = Local::now().date_naive();
+
+    // Today's target, reduced by appointments imported from the calendar
+    let schedule = schedule::load_schedule(app).unwrap_or_else(|e| {
+        println!("Failed to load calendar events: {}", e);
+        schedule::WorkSchedule::default()
+    });
+    let (day_plan, remaining_from_start2, expected_end) = plan_workday(&schedule, today, [start1, end1, start2]);
+    let total_target_minutes = day_plan.monitor_target_minutes();
+
+    let work_status = WorkStatus {
+        remaining_minutes: remaining_from_start2,
+        is_complete: false,
+        end_time: expected_end.format("%H:%M").to_string(),
+    };
+
+    // Update shared state
+    {
+        let mut state_guard = state.lock().unwrap();
+        *state_guard = Some(work_status);
+    }
+
+    // Keep today's punches in the local history used by reports and exports
+    if let Err(e) = history::record_day(app, today, &[start1, end1, start2], PunchSource::Manual) {
+        println!("Failed to record punches in history: {}", e);
+    }
+
+    if let Err(e) = ics::refresh_feed(app, Some(expected_end)) {
+        println!("Failed to update calendar feed: {}", e);
+    }
+
+    let times = TimeData {
+        inicio1: inicio1.to_string(),
+        fim1: fim1.to_string(),
+        inicio2: inicio2.to_string(),
+    };
+    let control = app.state::<MonitorControl>();
+    if let Some(previous) = control.task.lock().unwrap().take() {
+        previous.abort();
+    }
+    *control.session.lock().unwrap() = Some(ActiveSession { date: today, times: times.clone() });
+
+    events::publish(app, WorkEvent::MonitoringStarted {
+        times,
+        end_time: expected_end,
+        day_off: day_plan.day_off,
+        announce: should_announce_start(app, today, &expected_end.format("%H:%M").to_string()),
+    });
+
+    // Start background monitoring after MonitoringStarted, so the first status
+    // update reaches the listeners after it
+    let app_clone = app.clone();
+    let state_clone = state.clone();
+    let now = Local::now().naive_local();
+    let reminders: Vec<Reminder> = day_plan.reminders.into_iter().filter(|r| r.at > now).collect();
+    let lunch_return = Local.from_local_datetime(&today.and_time(start2)).unwrap();
+    let task = tokio::spawn(async move {
+        monitor_work_completion(app_clone, state_clone, lunch_return, expected_end, total_target_minutes, reminders).await;
+    });
+    *control.task.lock().unwrap() = Some(task);
+
+    Ok(())
+}
+
+/// Publishes the status every minute and the reminder, warning, completion and
+/// overtime events. Tray, notifications and channels react through the event bus.
+async fn monitor_work_completion(
+    app: AppHandle,
+    state: SharedState,
+    lunch_return: DateTime<Local>,
+    end_time: DateTime<Local>,
+    target_minutes: i64,
+    mut reminders: Vec<Reminder>,
+) {
+    let mut completion_notified = false;
+    let mut warning_published = false;
+    let mut overtime_hours_published = 0;
+
+    loop {
+        let now = Local::now();
+        let remaining = (end_time - now).num_minutes();
+
+        // Update state
+        {
+            let mut state_guard = state.lock().unwrap();
+            if let Some(ref mut status) = *state_guard {
+                status.remaining_minutes = remaining;
+                status.is_complete = remaining <= 0;
+            }
+        }
+        // Before returning from lunch only the morning period counts
+        let worked = target_minutes - (end_time - now.max(lunch_return)).num_minutes();
+        let next_reminder = reminders.iter().filter(|r| r.at > now.naive_local()).min_by_key(|r| r.at);
+        events::publish_status(&app, worked, remaining, target_minutes, end_time, next_reminder);
+
+        // Calendar reminders (appointments and meetings)
+        for reminder in reminders.iter().filter(|r| r.at <= now.naive_local()) {
+            events::publish(&app, WorkEvent::Reminder { title: reminder.title.clone(), message: reminder.message.clone() });
+            println!("Calendar reminder: {}", reminder.message);
+        }
+        reminders.retain(|r| r.at > now.naive_local());
+
+        // Overtime is tracked until the end of the day
+        if completion_notified && now.date_naive() != end_time.date_naive() {
+            end_work_monitoring(&app, &state);
+            break;
+        }
+
+        // Check if work is complete
+        if remaining <= 0 && !completion_notified {
+            events::publish(&app, WorkEvent::Complete { end_time });
+            println!("Work complete! Notifying user...");
+            completion_notified = true;
+        }
+
+        // Check if close to completion (3 minutes warning)
+        if remaining <= WARNING_MINUTES && remaining > 0 {
+            events::publish(&app, WorkEvent::AlmostComplete { remaining_minutes: remaining, end_time, first: !warning_published });
+            warning_published = true;
+            println!("Work almost complete: {} minutes remaining", remaining);
+        }
+
+        // Each full hour of overtime
+        let overtime_hours = -remaining / 60;
+        if completion_notified && overtime_hours > overtime_hours_published {
+            events::publish(&app, WorkEvent::Overtime { overtime_minutes: -remaining, end_time });
+            overtime_hours_published = overtime_hours;
+        }
+
+        // Sleep for 1 minute before next check
+        sleep(Duration::from_secs(60)).await;
+    }
+}
+
+/// A desktop notification: title, message, whether to show the overlay and
+/// whether to bring the main window to the foreground.
+struct DesktopNotification {
+    title: String,
+    message: String,
+    overlay: bool,
+    focus: bool,
+}
+
+fn show_desktop_notification(app: &AppHandle, notification: DesktopNotification) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let DesktopNotification { title, message, overlay, focus } = notification;
+        let _ = show_system_notification(app.clone(), title.clone(), message.clone()).await;
+        if overlay {
+            let _ = show_overlay_notification(app.clone(), title, message).await;
+        }
+        if focus {
+            if let Some(main_window) = app.get_webview_window("main") {
+                let _ = main_window.show();
+                let _ = main_window.set_focus();
+                let _ = main_window.unminimize();
+            }
+        }
+    });
+}
+
+/// Desktop notifications (system and overlay) for the monitor events. The
+/// snooze only silences these; the events still reach the other channels.
+fn listen_desktop_notifications(app: &AppHandle) {
+    // Reminders and the completion notice received while snoozed, shown when the snooze ends
+    let mut deferred: Vec<DesktopNotification> = Vec::new();
+
+    events::listen(app, move |app, event| {
+        let snoozed = app.state::<MonitorControl>().notifications_snoozed();
+        if !snoozed {
+            for notification in deferred.drain(..) {
+                show_desktop_notification(app, notification);
+            }
+        }
+
+        let (notification, deferrable) = match event {
+            WorkEvent::MonitoringStopped => {
+                deferred.clear();
+                return;
+            }
+            // Worked time on a day off (holiday, weekend, company day off) is all overtime
+            WorkEvent::MonitoringStarted { day_off: Some(reason), .. } => (DesktopNotification {
+                title: "📅 Dia de folga".to_string(),
+                message: format!("Hoje é dia de folga ({}): todo o tempo trabalhado conta como hora extra", reason),
+                overlay: false,
+                focus: false,
+            }, false),
+            WorkEvent::Reminder { title, message } => (DesktopNotification { title, message, overlay: true, focus: false }, true),
+            // Repeated every minute, so a later one replaces a snoozed one
+            WorkEvent::AlmostComplete { remaining_minutes, .. } => (DesktopNotification {
+                title: "⏰ Quase Acabando!".to_string(),
+                message: format!("Faltam apenas {} minutos para completar sua jornada!", remaining_minutes),
+                overlay: true,
+                focus: true,
+            }, false),
+            WorkEvent::Complete { .. } => (DesktopNotification {
+                title: "🎉 Jornada Completa!".to_string(),
+                message: "Parabéns! Você completou suas 8 horas de trabalho. Tenha um ótimo resto do dia!".to_string(),
+                overlay: true,
+                focus: true,
+            }, true),
+            _ => return,
+        };
+
+        if !snoozed {
+            show_desktop_notification(app, notification);
+        } else if deferrable {
+            deferred.push(notification);
+        }
+    });
+}
+
+#[tauri::command]
+fn get_work_status(state: State<'_, SharedState>) -> Option<WorkStatus> {
+    let state_guard = state.lock().unwrap();
+    state_guard.clone()
+}
+
+#[tauri::command]
+async fn notify_work_complete() -> Result<(), String> {
+    println!("Work completion notification triggered");
+    Ok(())
+}
+
+#[tauri::command]
+async fn start_monitoring() -> Result<(), String> {
+    println!("Starting monitoring mode");
+    Ok(())
+}
+
+#[tauri::command]
+pub(crate) async fn show_system_notification(app: AppHandle, title: String, message: String) -> Result<(), String> {
+    use tauri_plugin_notification::{NotificationExt, PermissionState};
+
+    println!("Attempting to show system notification: {} - {}", title, message);
+
+    // Check notification permission
+    match app.notification().permission_state() {
+        Ok(PermissionState::Granted) => {
+            println!("Notification permission granted, showing notification");
+
+            // Create and show the system notification
+            match app.notification()
+                .builder()
+                .title(&title)
+                .body(&message)
+                .icon("icon")
+                .show()
+            {
+                Ok(_) => {
+                    println!("System notification sent successfully");
+                    Ok(())
+                }
+                Err(e) => {
+                    println!("Failed to show system notification: {}", e);
+                    Err(format!("Failed to show system notification: {}", e))
+                }
+            }
+        }
+        Ok(PermissionState::Denied) => {
+            println!("Notification permission denied");
+            Err("Notification permission denied".to_string())
+        }
+        Ok(PermissionState::Prompt) | Ok(PermissionState::PromptWithRationale) => {
+            println!("Notification permission not set, requesting permission");
+
+            // Request permission first
+            match app.notification().request_permission() {
+                Ok(PermissionState::Granted) => {
+                    println!("Permission granted after request, showing notification");
+
+                    // Now show the notification
+                    match app.notification()
+                        .builder()
+                        .title(&title)
+                        .body(&message)
+                        .icon("icon")
+                        .show()
+                    {
+                        Ok(_) => {
+                            println!("System notification sent successfully after permission request");
+                            Ok(())
+                        }
+                        Err(e) => {
+                            println!("Failed to show system notification after permission request: {}", e);
+                            Err(format!("Failed to show system notification: {}", e))
+                        }
+                    }
+                }
+                Ok(_) => {
+                    println!("Permission denied after request");
+                    Err("Notification permission denied after request".to_string())
+                }
+                Err(e) => {
+                    println!("Error requesting notification permission: {}", e);
+                    Err(format!("Error requesting notification permission: {}", e))
+                }
+            }
+        }
+        Err(e) => {
+            println!("Error checking notification permission: {}", e);
+            Err(format!("Error checking notification permission: {}", e))
+        }
+    }
+}
+
+#[tauri::command]
+async fn show_overlay_notification(app: AppHandle, title: String, message: String) -> Result<(), String> {
+    println!("Showing overlay notification: {} - {}", title, message);
+
+    // Check if overlay window already exists and close it
+    if let Some(existing_window) = app.get_webview_window("notification_overlay") {
+        let _ = existing_window.close();
+        // Wait a moment to ensure the window is closed
+        sleep(Duration::from_millis(100)).await;
+    }
+
+    // Get primary monitor to calculate positioning
+    let primary_monitor = app.primary_monitor()
+        .map_err(|e| format!("Failed to get primary monitor: {}", e))?
+        .ok_or("No primary monitor found")?;
+
+    let monitor_size = primary_monitor.size();
+    let notification_width = 500.0;
+    let notification_height = 200.0;
+
+    // Calculate center position
+    let x_position = (monitor_size.width as f64 / 2.0) - (notification_width / 2.0);
+    let y_position = (monitor_size.height as f64 / 2.0) - (notification_height / 2.0); // Center vertically
+
+    // Create overlay notification window with inline HTML
+    let notification_html = format!(r#"
+<!DOCTYPE html>
+<html lang="pt-BR">
+<head>
+    <meta charset="UTF-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1.0">
+    <title>Expediente Concluído</title>
+    <style>
+        * {{
+            margin: 0;
+            padding: 0;
+            box-sizing: border-box;
+        }}
+
+        body {{
+            font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, 'Inter', sans-serif;
+            background: linear-gradient(135deg, #10b981 0%, #059669 100%);
+            color: white;
+            height: 100vh;
+            display: flex;
+            align-items: center;
+            justify-content: center;
+            overflow: hidden;
+            border-radius: 16px;
+            box-shadow: 0 20px 40px rgba(0, 0, 0, 0.4);
+            position: relative;
+        }}
+
+        .notification-container {{
+            text-align: center;
+            padding: 32px 24px;
+            max-width: 480px;
+            width: 100%;
+            animation: slideInScale 0.4s cubic-bezier(0.34, 1.56, 0.64, 1);
+        }}
+
+        .success-icon {{
+            font-size: 48px;
+            margin-bottom: 16px;
+            animation: bounce 0.6s ease-out 0.2s both;
+        }}
+
+        .notification-title {{
+            font-size: 24px;
+            font-weight: 700;
+            margin-bottom: 12px;
+            text-shadow: 0 2px 4px rgba(0, 0, 0, 0.3);
+            letter-spacing: -0.5px;
+        }}
+
+        .notification-message {{
+            font-size: 16px;
+            opacity: 0.95;
+            line-height: 1.5;
+            text-shadow: 0 1px 2px rgba(0, 0, 0, 0.2);
+            margin-bottom: 20px;
+        }}
+
+        .close-button {{
+            background: rgba(255, 255, 255, 0.2);
+            border: none;
+            color: white;
+            font-size: 14px;
+            font-weight: 600;
+            cursor: pointer;
+            padding: 10px 20px;
+            border-radius: 25px;
+            transition: all 0.2s ease;
+            backdrop-filter: blur(10px);
+        }}
+
+        .close-button:hover {{
+            background: rgba(255, 255, 255, 0.3);
+            transform: translateY(-1px);
+        }}
+
+        .close-button:active {{
+            transform: translateY(0);
+        }}
+
+        .progress-bar {{
+            position: absolute;
+            bottom: 0;
+            left: 0;
+            height: 4px;
+            background: rgba(255, 255, 255, 0.8);
+            width: 100%;
+            border-radius: 0 0 16px 16px;
+            animation: progressBar 8s linear;
+        }}
+
+        @keyframes slideInScale {{
+            from {{
+                opacity: 0;
+                transform: translateY(-30px) scale(0.9);
+            }}
+            to {{
+                opacity: 1;
+                transform: translateY(0) scale(1);
+            }}
+        }}
+
+        @keyframes bounce {{
+            from {{
+                opacity: 0;
+                transform: scale(0.3);
+            }}
+            50% {{
+                opacity: 1;
+                transform: scale(1.1);
+            }}
+            to {{
+                opacity: 1;
+                transform: scale(1);
+            }}
+        }}
+
+        @keyframes progressBar {{
+            from {{
+                width: 100%;
+            }}
+            to {{
+                width: 0%;
+            }}
+        }}
+    </style>
+</head>
+<body>
+    <div class="notification-container">
+        <div class="success-icon" id="icon">{}</div>
+        <div class="notification-title" id="title">{}</div>
+        <div class="notification-message" id="message">{}</div>
+        <button class="close-button" onclick="closeNotification()">Fechar</button>
+    </div>
+    <div class="progress-bar"></div>
+
+    <script>
+        const {{ invoke }} = window.__TAURI__.core;
+
+        // Close notification function
+        async function closeNotification() {{
+            try {{
+                await invoke('close_overlay_notification');
+            }} catch (error) {{
+                console.error('Error closing notification:', error);
+            }}
+        }}
+
+        // Play notification sound
+        function playNotificationSound() {{
+            try {{
+                const audioContext = new (window.AudioContext || window.webkitAudioContext)();
+
+                // Create success chime sound
+                const oscillator1 = audioContext.createOscillator();
+                const oscillator2 = audioContext.createOscillator();
+                const gainNode = audioContext.createGain();
+
+                oscillator1.connect(gainNode);
+                oscillator2.connect(gainNode);
+                gainNode.connect(audioContext.destination);
+
+                // Success chime frequencies
+                oscillator1.frequency.setValueAtTime(523.25, audioContext.currentTime); // C5
+                oscillator1.frequency.setValueAtTime(659.25, audioContext.currentTime + 0.1); // E5
+                oscillator1.frequency.setValueAtTime(783.99, audioContext.currentTime + 0.2); // G5
+
+                oscillator2.frequency.setValueAtTime(523.25 * 2, audioContext.currentTime + 0.1); // C6
+                oscillator2.frequency.setValueAtTime(659.25 * 2, audioContext.currentTime + 0.2); // E6
+
+                gainNode.gain.setValueAtTime(0.1, audioContext.currentTime);
+                gainNode.gain.exponentialRampToValueAtTime(0.01, audioContext.currentTime + 0.5);
+
+                oscillator1.start(audioContext.currentTime);
+                oscillator1.stop(audioContext.currentTime + 0.5);
+
+                oscillator2.start(audioContext.currentTime + 0.1);
+                oscillator2.stop(audioContext.currentTime + 0.4);
+            }} catch (error) {{
+                console.log('Could not play notification sound:', error);
+            }}
+        }}
+
+        // Play sound when notification appears
+        window.addEventListener('DOMContentLoaded', () => {{
+            setTimeout(() => {{
+                playNotificationSound();
+            }}, 200);
+        }});
+
+        // Auto-close after 8 seconds
+        setTimeout(() => {{
+            closeNotification();
+        }}, 8000);
+    </script>
+</body>
+</html>
+"#,
+        // Extract icon from title or use default
+        if title.contains("🎉") { "🎉" }
+        else if title.contains("⏰") { "⏰" }
+        else if title.contains("🧪") { "🧪" }
+        else { "✅" },
+        title,
+        message
+    );
+
+    let overlay_window = WebviewWindowBuilder::new(
+        &app,
+        "notification_overlay",
+        tauri::WebviewUrl::App(format!("data:text/html;charset=utf-8,{}", urlencoding::encode(&notification_html)).parse().unwrap())
+    )
+    .title("Notificação")
+    .inner_size(notification_width, notification_height)
+    .position(x_position, y_position)
+    .resizable(false)
+    .minimizable(false)
+    .maximizable(false)
+    .decorations(false)
+    .always_on_top(true)
+    .skip_taskbar(true)
+    .focused(true)
+    .build()
+    .map_err(|e| format!("Failed to create overlay window: {}", e))?;
+
+    // No need to send notification data as it's embedded in HTML
+
+    // Auto-close after 8 seconds (same as frontend)
+    let overlay_window_clone = overlay_window.clone();
+    tokio::spawn(async move {
+        sleep(Duration::from_secs(8)).await;
+        let _ = overlay_window_clone.close();
+    });
+
+    Ok(())
+}
+
+#[tauri::command]
+async fn close_overlay_notification(app: AppHandle) -> Result<(), String> {
+    if let Some(overlay_window) = app.get_webview_window("notification_overlay") {
+        let _ = overlay_window.close();
+    }
+    Ok(())
+}
+
+#[tauri::command]
+async fn save_pontomais_config(app: AppHandle, config: String) -> Result<(), String> {
+    let encrypted_config = encrypt_data(&config)?;
+
+    let store = app.store("noponto.dat")
+        .map_err(|e| format!("Failed to get store: {}", e))?;
+
+    store.set("pontomais_config", serde_json::Value::String(encrypted_config.clone()));
+
+    store.save()
+        .map_err(|e| format!("Failed to save store: {}", e))?;
+
+    println!("PontoMais config saved successfully");
+    Ok(())
+}
+
+#[tauri::command]
+async fn get_pontomais_config(app: AppHandle) -> Result<String, String> {
+    let store = app.store("noponto.dat")
+        .map_err(|e| format!("Failed to get store: {}", e))?;
+
+    read_pontomais_config(store.as_ref())
+}
+
+#[tauri::command]
+async fn test_pontomais_api(config: String) -> Result<String, String> {
+    let provider = provider_from_config(&config)?;
+    provider.test_connection().await
+}
+
+#[tauri::command]
+async fn stop_work_monitoring(app: AppHandle, state: State<'_, SharedState>) -> Result<(), String> {
+    end_work_monitoring(&app, state.inner());
+    Ok(())
+}
+
+pub(crate) fn end_work_monitoring(app: &AppHandle, state: &SharedState) {
+    println!("Stopping work monitoring");
+
+    let control = app.state::<MonitorControl>();
+    if let Some(task) = control.task.lock().unwrap().take() {
+        task.abort();
+    }
+    *control.session.lock().unwrap() = None;
+    *state.lock().unwrap() = None;
+
+    if let Err(e) = ics::refresh_feed(app, None) {
+        println!("Failed to update calendar feed: {}", e);
+    }
+
+    events::publish(app, WorkEvent::MonitoringStopped);
+}
+
+/// Runs once when the app is exiting: stops the HTTP API, the D-Bus service,
+/// the monitor, the email scheduler and MQTT, in this order, then persists
+/// the active session, closes the overlay and flushes the store to disk.
+pub(crate) fn shutdown(app: &AppHandle) {
+    println!("Shutting down NoPonto");
+
+    // Services that take commands go first, so they can't restart the monitor
+    tauri::async_runtime::block_on(async {
+        http_api::stop(app).await;
+        #[cfg(target_os = "linux")]
+        dbus::stop(app).await;
+    });
+
+    let control = app.state::<MonitorControl>();
+    if let Some(task) = control.task.lock().unwrap().take() {
+        task.abort();
+    }
+
+    email::stop_scheduler(app);
+    // Last, so the broker gets the final "stopped" status and "offline"
+    tauri::async_runtime::block_on(mqtt::stop(app));
+
+    match app.store("noponto.dat") {
+        Ok(store) => {
+            match control.session.lock().unwrap().as_ref().and_then(|session| serde_json::to_value(session).ok()) {
+                Some(value) => store.set(ACTIVE_SESSION_KEY, value),
+                None => {
+                    store.delete(ACTIVE_SESSION_KEY);
+                }
+            }
+            if let Err(e) = store.save() {
+                println!("Failed to save store on shutdown: {}", e);
+            }
+        }
+        Err(e) => println!("Failed to get store on shutdown: {}", e),
+    }
+
+    if let Some(overlay_window) = app.get_webview_window("notification_overlay") {
+        let _ = overlay_window.close();
+    }
+
+    control::remove_endpoint();
+}
+
+/// Handles the command line of a second launch: `--start`, `--stop` and
+/// `--sync` run the matching tray actions; the main window is shown unless
+/// `--minimized` is passed.
+fn handle_instance_args(app: &AppHandle, args: &[String]) {
+    let has_flag = |flag: &str| args.iter().any(|arg| arg == flag);
+
+    if has_flag("--start") {
+        tray::start_monitoring(app);
+    }
+    if has_flag("--stop") {
+        end_work_monitoring(app, app.state::<SharedState>().inner());
+    }
+    if has_flag("--sync") {
+        tray::sync_from_provider(app);
+    }
+    if !has_flag("--minimized") {
+        tray::show_main_window(app);
+    }
+}
+
+/// Starts monitoring with the times last saved by the frontend.
+pub(crate) async fn start_monitoring_from_store(app: &AppHandle) -> Result<(), String> {
+    let store = app.store("noponto.dat")
+        .map_err(|e| format!("Failed to get store: {}", e))?;
+
+    let time_data: TimeData = store
+        .get("timeData")
+        .and_then(|value| serde_json::from_value(value).ok())
+        .filter(|data: &TimeData| !data.inicio1.is_empty() && !data.fim1.is_empty() && !data.inicio2.is_empty())
+        .ok_or("Preencha os horários de entrada, saída para o almoço e retorno antes de iniciar o monitoramento.")?;
+
+    let state = app.state::<SharedState>();
+    begin_work_monitoring(app, state.inner(), &time_data.inicio1, &time_data.fim1, &time_data.inicio2).await
+}
+
+/// Saves the times shown in the main window and updates the frontend.
+pub(crate) fn save_time_data(app: &AppHandle, time_data: &TimeData) -> Result<(), String> {
+    let store = app.store("noponto.dat")
+        .map_err(|e| format!("Failed to get store: {}", e))?;
+    let value = serde_json::to_value(time_data)
+        .map_err(|e| format!("Failed to serialize time data: {}", e))?;
+    store.set("timeData", value);
+    store.save()
+        .map_err(|e| format!("Failed to save store: {}", e))?;
+
+    let _ = app.emit("time_data_updated", time_data);
+    Ok(())
+}
+
+/// Busca as marcações de hoje no sistema de ponto, salva como `timeData` e,
+/// com entrada, almoço e retorno registrados, inicia o monitoramento.
+pub(crate) async fn sync_and_start_monitoring(app: &AppHandle) -> Result<String, String> {
+    let hours = fetch_pontomais_hours(app.clone()).await?;
+
+    let time_data = TimeData {
+        inicio1: hours.first().cloned().unwrap_or_default(),
+        fim1: hours.get(1).cloned().unwrap_or_default(),
+        inicio2: hours.get(2).cloned().unwrap_or_default(),
+    };
+
+    save_time_data(app, &time_data)?;
+
+    match hours.len() {
+        0 => Ok("Não foram encontrados registros de ponto para hoje.".to_string()),
+        3 => {
+            start_monitoring_from_store(app).await?;
+            Ok("Horários importados e monitoramento iniciado.".to_string())
+        }
+        count => Ok(format!("{} horário(s) importados do sistema de ponto.", count)),
+    }
+}
+
+/// Carrega o sistema de ponto configurado pelo usuário.
+async fn configured_provider(app: AppHandle) -> Result<Box<dyn TimeCardProvider>, String> {
+    let config_json = get_pontomais_config(app).await?;
+
+    if config_json.is_empty() {
+        return Err("Configurações do PontoMais não encontradas. Configure primeiro na tela de configurações.".to_string());
+    }
+
+    provider_from_config(&config_json)
+}
+
+#[tauri::command]
+fn list_time_card_providers() -> Vec<ProviderInfo> {
+    AVAILABLE_PROVIDERS.to_vec()
+}
+
+#[tauri::command]
+async fn fetch_pontomais_hours(app: AppHandle) -> Result<Vec<String>, String> {
+    let result = fetch_today_hours(&app).await;
+    if let Err(e) = &result {
+        events::publish(&app, WorkEvent::SyncFailed { error: e.clone() });
+    }
+    result
+}
+
+pub(crate) async fn fetch_today_hours(app: &AppHandle) -> Result<Vec<String>, String> {
+    let provider = configured_provider(app.clone()).await?;
+
+    let today = Local::now().date_naive();
+    let punches = provider.fetch_punches(today, today).await?;
+
+    let punch_times: Vec<NaiveTime> = punches.iter().map(|punch| punch.time).collect();
+    if let Err(e) = history::record_day(app, today, &punch_times, PunchSource::Provider) {
+        println!("Failed to record punches in history: {}", e);
+    } else if let Err(e) = ics::refresh_feed_from_state(app) {
+        println!("Failed to update calendar feed: {}", e);
+    }
+
+    // Extrair os horários
+    let times: Vec<String> = punches
+        .iter()
+        .map(|punch| punch.time.format("%H:%M").to_string())
+        .collect();
+
+    println!("=== HORÁRIOS ENCONTRADOS ===");
+    println!("Total de registros: {}", times.len());
+    for (i, time) in times.iter().enumerate() {
+        println!("Registro {}: {}", i + 1, time);
+    }
+    println!("===========================");
+
+    Ok(times)
+}
+
+#[tauri::command]
+async fn register_punch(app: AppHandle) -> Result<String, String> {
+    let provider = configured_provider(app).await?;
+    let punch = provider.register_punch().await?;
+
+    println!("Punch registered on {}: {}", provider.id(), punch.time.format("%H:%M"));
+    Ok(punch.time.format("%H:%M").to_string())
+}
+
+
+#[cfg_attr(mobile, tauri::mobile_entry_point)]
+pub fn run() {
+    let shared_state: SharedState = Arc::new(Mutex::new(None));
+
+    let mut builder = tauri::Builder::default();
+
+    // A second launch forwards its arguments here and exits
+    #[cfg(desktop)]
+    {
+        builder = builder.plugin(tauri_plugin_single_instance::init(|app, args, _cwd| {
+            println!("Second instance launched with args: {:?}", args);
+            handle_instance_args(app, &args);
+        }));
+        builder = builder.plugin(
+            tauri_plugin_autostart::Builder::new()
+                .args([autostart::AUTOSTART_ARG])
+                .build(),
+        );
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        builder = builder.manage(dbus::DbusService::default());
+    }
+
+    builder
+        .plugin(tauri_plugin_opener::init())
+        .plugin(tauri_plugin_store::Builder::default().build())
+        .plugin(tauri_plugin_notification::init())
+        .plugin(tauri_plugin_dialog::init())
+        .manage(shared_state)
+        .manage(MonitorControl::default())
+        .manage(http_api::HttpApi::default())
+        .manage(events::EventBus::default())
+        .manage(mqtt::Mqtt::default())
+        .manage(email::EmailScheduler::default())
+        .invoke_handler(tauri::generate_handler![
+            greet,
+            start_work_monitoring,
+            stop_work_monitoring,
+            get_work_status,
+            events::get_status_snapshot,
+            notify_work_complete,
+            start_monitoring,
+            show_system_notification,
+            show_overlay_notification,
+            close_overlay_notification,
+            save_pontomais_config,
+            get_pontomais_config,
+            test_pontomais_api,
+            fetch_pontomais_hours,
+            list_time_card_providers,
+            register_punch,
+            punch_import::preview_punch_import,
+            punch_import::import_punches,
+            afd::import_afd,
+            afd::export_afd,
+            export::export_timesheet,
+            ics::export_work_calendar,
+            ics::set_calendar_feed_path,
+            ics::get_calendar_feed_path,
+            schedule::import_calendar_file,
+            schedule::import_caldav_calendar,
+            schedule::get_day_plan,
+            holidays::get_holiday_config,
+            holidays::save_holiday_config,
+            holidays::get_holidays,
+            report::get_work_report,
+            http_api::get_http_api_settings,
+            http_api::save_http_api_settings,
+            webhooks::get_webhook_config,
+            webhooks::save_webhook_config,
+            webhooks::get_webhook_deliveries,
+            webhooks::test_webhook,
+            chat::get_chat_channels,
+            chat::save_chat_channels,
+            chat::get_chat_deliveries,
+            chat::test_chat_channel,
+            push::get_push_channels,
+            push::save_push_channels,
+            push::get_push_deliveries,
+            push::test_push_channel,
+            email::get_email_settings,
+            email::save_email_settings,
+            email::send_test_email,
+            mqtt::get_mqtt_settings,
+            mqtt::save_mqtt_settings,
+            #[cfg(desktop)]
+            autostart::get_startup_settings,
+            #[cfg(desktop)]
+            autostart::save_startup_settings
+        ])
+        .setup(|app| {
+            // Create system tray
+            tray::create_system_tray(app.handle())?;
+
+            // Event bus listeners, registered before anything can start the monitor
+            events::forward_to_frontend(app.handle());
+            listen_desktop_notifications(app.handle());
+            tray::listen(app.handle());
+            webhooks::listen(app.handle());
+            chat::listen(app.handle());
+            push::listen(app.handle());
+            mqtt::listen(app.handle());
+            #[cfg(target_os = "linux")]
+            dbus::listen(app.handle());
+
+            // Local channel used by the command-line interface
+            let control_app = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = control::serve(control_app).await {
+                    println!("Failed to start control socket: {}", e);
+                }
+            });
+
+            // Daily and weekly email summaries
+            email::start_scheduler(app.handle());
+
+            // Session bus service for desktop widgets
+            #[cfg(target_os = "linux")]
+            {
+                let dbus_app = app.handle().clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = dbus::serve(dbus_app).await {
+                        println!("Failed to start D-Bus service: {}", e);
+                    }
+                });
+            }
+
+            // Optional HTTP API for scripts and editor plugins
+            let api_app = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = http_api::apply_settings(&api_app).await {
+                    println!("Failed to start HTTP API: {}", e);
+                }
+            });
+
+            // MQTT publishing for home automation
+            if let Err(e) = mqtt::apply_settings(app.handle()) {
+                println!("Failed to start MQTT: {}", e);
+            }
+
+            // The window starts hidden so autostart can go straight to the tray
+            #[cfg(desktop)]
+            {
+                if !autostart::should_start_minimized(app.handle()) {
+                    tray::show_main_window(app.handle());
+                }
+                autostart::run_startup_action(app.handle());
+            }
+            #[cfg(not(desktop))]
+            tray::show_main_window(app.handle());
+            
+            // Prevent the app from closing when the window is closed
+            let main_window = app.get_webview_window("main").unwrap();
+            
+            let app_handle = app.handle().clone();
+            main_window.on_window_event(move |event| {
+                if let WindowEvent::CloseRequested { api, .. } = event {
+                    api.prevent_close();
+                    
+                    // Hide the window instead of closing the app
+                    if let Some(window) = app_handle.get_webview_window("main") {
+                        let _ = window.hide();
+                    }
+                }
+            });
+            
+            Ok(())
+        })
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            if let RunEvent::ExitRequested { .. } = event {
+                shutdown(app);
+            }
+        });
+}
\ No newline at end of file
@@ -0,0 +1,122 @@
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_autostart::ManagerExt;
+use tauri_plugin_store::StoreExt;
+
+use crate::{tray, ActiveSession, SharedState, ACTIVE_SESSION_KEY};
+
+const SETTINGS_KEY: &str = "startup_settings";
+/// Argumento passado pela entrada de inicialização automática do sistema.
+pub const AUTOSTART_ARG: &str = "--autostart";
+pub const MINIMIZED_ARG: &str = "--minimized";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StartupAction {
+    #[default]
+    None,
+    /// Retoma o monitoramento de hoje que estava ativo ao fechar o app.
+    Resume,
+    /// Busca as marcações de hoje no sistema de ponto.
+    Sync,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StartupSettings {
+    /// Ao ler as configurações, reflete a entrada de inicialização instalada no sistema.
+    #[serde(default)]
+    pub autostart: bool,
+    #[serde(default)]
+    pub start_minimized: bool,
+    #[serde(default)]
+    pub startup_action: StartupAction,
+}
+
+fn load_settings(app: &AppHandle) -> Result<StartupSettings, String> {
+    let store = app.store("noponto.dat")
+        .map_err(|e| format!("Failed to get store: {}", e))?;
+
+    match store.get(SETTINGS_KEY) {
+        Some(value) => serde_json::from_value(value)
+            .map_err(|e| format!("Failed to parse startup settings: {}", e)),
+        None => Ok(StartupSettings::default()),
+    }
+}
+
+/// Se a janela principal deve ficar oculta nesta execução: `--minimized` na
+/// linha de comando ou inicialização automática com "iniciar minimizado".
+pub fn should_start_minimized(app: &AppHandle) -> bool {
+    let args: Vec<String> = std::env::args().collect();
+    if args.iter().any(|arg| arg == MINIMIZED_ARG) {
+        return true;
+    }
+
+    args.iter().any(|arg| arg == AUTOSTART_ARG)
+        && load_settings(app).map(|settings| settings.start_minimized).unwrap_or(false)
+}
+
+/// Executa a ação configurada para a abertura do app.
+pub fn run_startup_action(app: &AppHandle) {
+    let action = match load_settings(app) {
+        Ok(settings) => settings.startup_action,
+        Err(e) => {
+            println!("Failed to load startup settings: {}", e);
+            return;
+        }
+    };
+
+    match action {
+        StartupAction::None => {}
+        StartupAction::Sync => tray::sync_from_provider(app),
+        StartupAction::Resume => {
+            let session: Option<ActiveSession> = app
+                .store("noponto.dat")
+                .ok()
+                .and_then(|store| store.get(ACTIVE_SESSION_KEY))
+                .and_then(|value| serde_json::from_value(value).ok());
+
+            let Some(session) = session.filter(|session| session.date == Local::now().date_naive()) else {
+                return;
+            };
+
+            println!("Resuming work monitoring from {}", session.date);
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                let state = app.state::<SharedState>().inner().clone();
+                let times = session.times;
+                if let Err(e) = crate::begin_work_monitoring(&app, &state, &times.inicio1, &times.fim1, &times.inicio2).await {
+                    println!("Failed to resume work monitoring: {}", e);
+                }
+            });
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn get_startup_settings(app: AppHandle) -> Result<StartupSettings, String> {
+    let mut settings = load_settings(&app)?;
+    settings.autostart = app.autolaunch().is_enabled()
+        .map_err(|e| format!("Failed to read autostart state: {}", e))?;
+    Ok(settings)
+}
+
+/// Instala ou remove a entrada de inicialização automática (XDG autostart no
+/// Linux, LaunchAgent no macOS, registro no Windows) e salva as preferências.
+#[tauri::command]
+pub async fn save_startup_settings(app: AppHandle, settings: StartupSettings) -> Result<(), String> {
+    let autolaunch = app.autolaunch();
+    let result = if settings.autostart { autolaunch.enable() } else { autolaunch.disable() };
+    result.map_err(|e| format!("Failed to update autostart: {}", e))?;
+
+    let store = app.store("noponto.dat")
+        .map_err(|e| format!("Failed to get store: {}", e))?;
+
+    let value = serde_json::to_value(&settings)
+        .map_err(|e| format!("Failed to serialize startup settings: {}", e))?;
+    store.set(SETTINGS_KEY, value);
+
+    store.save()
+        .map_err(|e| format!("Failed to save store: {}", e))
+}
@@ -0,0 +1,3 @@
+fn main() {
+    std::process::exit(noponto_lib::cli::main())
+}
@@ -0,0 +1,209 @@
+//! Mensagens para webhooks de entrada do Slack, Mattermost e Microsoft Teams
+//! (ex.: "saindo às 17:40" ao iniciar o dia), com novas tentativas por canal
+//! e registro próprio das entregas. Os canais são salvos criptografados.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+
+use crate::delivery::{self, Delivery};
+use crate::events;
+use crate::timesheet::format_minutes;
+use crate::webhooks::{NotificationEvent, NotificationPayload};
+
+const CONFIG_KEY: &str = "chat_channels";
+const DELIVERIES_KEY: &str = "chat_deliveries";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChatPlatform {
+    Slack,
+    Mattermost,
+    Teams,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatChannel {
+    pub name: String,
+    pub platform: ChatPlatform,
+    /// URL do webhook de entrada gerada pelo serviço.
+    pub url: String,
+    #[serde(default = "default_events")]
+    pub events: Vec<NotificationEvent>,
+    /// Texto por evento, com `{end}`, `{remaining}` e `{message}`. Sem
+    /// texto, usa a mensagem padrão do evento.
+    #[serde(default)]
+    pub messages: HashMap<NotificationEvent, String>,
+    /// Nome exibido no Slack e no Mattermost, quando o webhook permite.
+    #[serde(default)]
+    pub username: Option<String>,
+    /// Tentativas por mensagem, com espera dobrando a cada falha.
+    #[serde(default = "delivery::default_max_attempts")]
+    pub max_attempts: u32,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_events() -> Vec<NotificationEvent> {
+    vec![NotificationEvent::Start, NotificationEvent::Complete]
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_text(payload: &NotificationPayload) -> String {
+    let end = payload.end_time.as_deref().unwrap_or("--:--");
+    match payload.event {
+        NotificationEvent::Start => format!("🕗 Saindo às {}", end),
+        NotificationEvent::Complete => format!("🏁 Jornada completa, saindo às {}", end),
+        _ => payload.message.clone(),
+    }
+}
+
+fn message_text(channel: &ChatChannel, payload: &NotificationPayload) -> String {
+    match channel.messages.get(&payload.event) {
+        Some(template) => template
+            .replace("{end}", payload.end_time.as_deref().unwrap_or("--:--"))
+            .replace("{remaining}", &format_minutes(payload.remaining_minutes.unwrap_or(0)))
+            .replace("{message}", &payload.message),
+        None => default_text(payload),
+    }
+}
+
+/// Corpo no formato esperado por cada serviço.
+fn chat_body(channel: &ChatChannel, payload: &NotificationPayload) -> Value {
+    let text = message_text(channel, payload);
+
+    match channel.platform {
+        ChatPlatform::Slack | ChatPlatform::Mattermost => {
+            let mut body = json!({ "text": text });
+            if let Some(username) = &channel.username {
+                body["username"] = json!(username);
+            }
+            body
+        }
+        // Adaptive Card, aceito pelos conectores antigos e pelos fluxos do Workflows
+        ChatPlatform::Teams => json!({
+            "type": "message",
+            "attachments": [{
+                "contentType": "application/vnd.microsoft.card.adaptive",
+                "content": {
+                    "$schema": "http://adaptivecards.io/schemas/adaptive-card.json",
+                    "type": "AdaptiveCard",
+                    "version": "1.4",
+                    "body": [
+                        { "type": "TextBlock", "text": payload.title, "weight": "Bolder" },
+                        { "type": "TextBlock", "text": text, "wrap": true }
+                    ]
+                }
+            }]
+        }),
+    }
+}
+
+async fn post(channel: &ChatChannel, payload: &NotificationPayload, max_attempts: u32) -> Delivery {
+    let request = delivery::Request::post(&channel.name, &channel.url);
+    delivery::send_with_retries(&request, payload.event, Ok(chat_body(channel, payload)), max_attempts).await
+}
+
+pub fn load_chat_channels(app: &AppHandle) -> Result<Vec<ChatChannel>, String> {
+    let store = app.store("noponto.dat")
+        .map_err(|e| format!("Failed to get store: {}", e))?;
+
+    match store.get(CONFIG_KEY) {
+        Some(Value::String(encrypted)) => {
+            let json = crate::decrypt_data(&encrypted)?;
+            serde_json::from_str(&json)
+                .map_err(|e| format!("Failed to parse chat channels: {}", e))
+        }
+        // Versões anteriores salvavam os canais abertos; criptografa na primeira leitura
+        Some(value) => {
+            let channels: Vec<ChatChannel> = serde_json::from_value(value)
+                .map_err(|e| format!("Failed to parse chat channels: {}", e))?;
+            store_chat_channels(app, &channels)?;
+            Ok(channels)
+        }
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Salva os canais criptografados: as URLs dos webhooks de entrada dão acesso ao canal.
+fn store_chat_channels(app: &AppHandle, channels: &[ChatChannel]) -> Result<(), String> {
+    let json = serde_json::to_string(channels)
+        .map_err(|e| format!("Failed to serialize chat channels: {}", e))?;
+    let encrypted = crate::encrypt_data(&json)?;
+
+    let store = app.store("noponto.dat")
+        .map_err(|e| format!("Failed to get store: {}", e))?;
+    store.set(CONFIG_KEY, Value::String(encrypted));
+    store.save()
+        .map_err(|e| format!("Failed to save store: {}", e))
+}
+
+/// Publica o evento, em segundo plano, nos canais inscritos nele.
+fn dispatch(app: &AppHandle, payload: NotificationPayload) {
+    let channels = match load_chat_channels(app) {
+        Ok(channels) => channels,
+        Err(e) => {
+            println!("Failed to load chat channels: {}", e);
+            return;
+        }
+    };
+
+    for channel in channels.into_iter().filter(|channel| channel.enabled && channel.events.contains(&payload.event)) {
+        let app = app.clone();
+        let payload = payload.clone();
+        tauri::async_runtime::spawn(async move {
+            let delivery = post(&channel, &payload, channel.max_attempts).await;
+            if !delivery.success {
+                println!("Chat message to {} failed: {}", channel.name, delivery.error.as_deref().unwrap_or(""));
+            }
+            if let Err(e) = delivery::record(&app, DELIVERIES_KEY, delivery) {
+                println!("Failed to record chat delivery: {}", e);
+            }
+        });
+    }
+}
+
+/// Assina o barramento de eventos e publica os eventos nos canais de chat.
+pub fn listen(app: &AppHandle) {
+    events::listen(app, |app, event| {
+        if let Some(payload) = NotificationPayload::from_event(&event) {
+            dispatch(app, payload);
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn get_chat_channels(app: AppHandle) -> Result<Vec<ChatChannel>, String> {
+    load_chat_channels(&app)
+}
+
+#[tauri::command]
+pub async fn save_chat_channels(app: AppHandle, channels: Vec<ChatChannel>) -> Result<(), String> {
+    for channel in &channels {
+        reqwest::Url::parse(&channel.url)
+            .map_err(|e| format!("URL inválida para o canal {}: {}", channel.name, e))?;
+    }
+
+    store_chat_channels(&app, &channels)
+}
+
+#[tauri::command]
+pub async fn get_chat_deliveries(app: AppHandle) -> Result<Vec<Delivery>, String> {
+    delivery::load_log(&app, DELIVERIES_KEY)
+}
+
+/// Publica uma mensagem de início de dia de exemplo no canal.
+#[tauri::command]
+pub async fn test_chat_channel(app: AppHandle, channel: ChatChannel) -> Result<Delivery, String> {
+    let mut payload = NotificationPayload::new(NotificationEvent::Start, "🧪 Teste do NoPonto", "Mensagem de teste");
+    payload.end_time = Some("17:40".to_string());
+
+    let delivery = post(&channel, &payload, 1).await;
+    delivery::record(&app, DELIVERIES_KEY, delivery.clone())?;
+    Ok(delivery)
+}
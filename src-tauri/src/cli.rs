@@ -0,0 +1,462 @@
+//! Interface de linha de comando (`noponto`). Com o app aberto, os
+//! comandos são enviados pelo canal de controle; sem ele, a CLI lê e grava o
+//! `noponto.dat` diretamente, usando os mesmos cálculos do app.
+
+use chrono::{Datelike, Duration, Local, NaiveDate, NaiveTime, TimeZone};
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpStream;
+
+use crate::control::{self, ControlCommand, ControlRequest, ControlResponse};
+use crate::history::{self, PunchSource};
+use crate::ics;
+use crate::providers::provider_from_config;
+use crate::schedule;
+use crate::status::{StatusSnapshot, WorkPhase};
+use crate::storage::{FileStore, KeyValueStore};
+use crate::timesheet::{self, format_minutes, weekday_name};
+use crate::TimeData;
+
+const USAGE: &str = "Uso: noponto <comando>
+
+Comandos:
+  status                          Tempo restante e saída prevista de hoje
+  start <entrada> <almoço> <retorno>
+                                  Inicia o monitoramento (ex.: start 08:00 12:00 13:00)
+  stop                            Para o monitoramento do app
+  sync                            Busca as marcações de hoje no sistema de ponto
+  history [--week | --month]      Espelho da semana (padrão) ou do mês atual
+  bar [--waybar] [--format <modelo>] [--once]
+                                  Status contínuo para waybar, polybar, tmux e i3blocks
+
+Modelos de --format: {remaining} (tempo restante ou +hora extra),
+{end} (saída prevista), {status} (texto completo). Padrão: \"{remaining} → {end}\"";
+
+const DEFAULT_BAR_FORMAT: &str = "{remaining} → {end}";
+
+/// Intervalo entre tentativas de reconectar ao app no modo `bar`.
+const BAR_RETRY_SECONDS: u64 = 30;
+
+struct BarOptions {
+    waybar: bool,
+    format: String,
+    once: bool,
+}
+
+enum HistoryRange {
+    Week,
+    Month,
+}
+
+/// Conecta ao app em execução e envia o comando. `None` quando o app não está aberto.
+fn connect_to_app(command: ControlCommand) -> Option<Result<TcpStream, String>> {
+    let endpoint = control::read_endpoint()?;
+    let address = std::net::SocketAddr::from(([127, 0, 0, 1], endpoint.port));
+    let stream = TcpStream::connect_timeout(&address, std::time::Duration::from_secs(1)).ok()?;
+
+    let result = (|| {
+        let mut request = serde_json::to_string(&ControlRequest { token: endpoint.token, command })
+            .map_err(|e| format!("Failed to serialize request: {}", e))?;
+        request.push('\n');
+        (&stream).write_all(request.as_bytes())
+            .map_err(|e| format!("Failed to send request: {}", e))?;
+        Ok(stream)
+    })();
+
+    Some(result)
+}
+
+fn read_response(reader: &mut impl BufRead) -> Result<ControlResponse, String> {
+    let mut line = String::new();
+    let read = reader.read_line(&mut line)
+        .map_err(|e| format!("Failed to read response: {}", e))?;
+    if read == 0 {
+        return Err("Conexão encerrada pelo app".to_string());
+    }
+    serde_json::from_str(&line).map_err(|e| format!("Invalid response: {}", e))
+}
+
+/// Envia um comando ao app em execução. `None` quando o app não está aberto.
+fn send_to_app(command: ControlCommand) -> Option<Result<ControlResponse, String>> {
+    let result = connect_to_app(command)?
+        .and_then(|stream| read_response(&mut BufReader::new(&stream)));
+    Some(result)
+}
+
+fn print_status(status: Option<StatusSnapshot>) {
+    match status {
+        Some(status) => println!("{}", status.summary()),
+        None => println!("Monitoramento parado"),
+    }
+}
+
+fn app_response(response: Result<ControlResponse, String>) -> Result<serde_json::Value, String> {
+    let response = response?;
+    if response.ok {
+        Ok(response.data)
+    } else {
+        Err(response.error.unwrap_or_else(|| "Erro desconhecido".to_string()))
+    }
+}
+
+/// Status de hoje calculado pelo histórico local; só existe com as três
+/// primeiras marcações (entrada, almoço e retorno).
+fn standalone_work_status(store: &FileStore, times: &[NaiveTime]) -> Result<Option<StatusSnapshot>, String> {
+    let [inicio1, fim1, inicio2] = times else { return Ok(None) };
+
+    let schedule = schedule::read_schedule(store)?;
+    let now = Local::now();
+    let today = now.date_naive();
+    let (day_plan, _, expected_end) = crate::plan_workday(&schedule, today, [*inicio1, *fim1, *inicio2]);
+    let target = day_plan.monitor_target_minutes();
+    let remaining = (expected_end - now).num_minutes();
+
+    // Mesmo cálculo do monitoramento: antes do retorno do almoço só conta a manhã
+    let lunch_return = Local.from_local_datetime(&today.and_time(*inicio2)).unwrap();
+    let worked = target - (expected_end - now.max(lunch_return)).num_minutes();
+    let next_reminder = day_plan.reminders.iter()
+        .filter(|reminder| reminder.at > now.naive_local())
+        .min_by_key(|reminder| reminder.at);
+
+    Ok(Some(StatusSnapshot::new(worked, remaining, target, expected_end, next_reminder, None)))
+}
+
+/// Status calculado a partir das marcações de hoje no histórico local.
+fn standalone_status(store: &FileStore) -> Result<(), String> {
+    let times = history::read_history(store)?.times(Local::now().date_naive());
+
+    match times.len() {
+        0 => println!("Nenhuma marcação hoje"),
+        1 | 2 => println!("Trabalhado hoje: {} — aguardando o retorno do almoço", format_minutes(history::worked_minutes(&times))),
+        3 => print_status(standalone_work_status(store, &times)?),
+        _ => println!("Trabalhado hoje: {}", format_minutes(history::worked_minutes(&times))),
+    }
+
+    Ok(())
+}
+
+/// Linha de status para barras: texto puro ou JSON do módulo custom do waybar.
+fn bar_line(status: Option<&StatusSnapshot>, options: &BarOptions) -> String {
+    let (text, tooltip, class) = match status {
+        Some(status) => {
+            let full = status.summary();
+            let class = match status.phase {
+                WorkPhase::Working => "working",
+                WorkPhase::FinalMinutes => "warning",
+                WorkPhase::Complete => "complete",
+                WorkPhase::Overtime => "overtime",
+            };
+            let text = options.format
+                .replace("{remaining}", &status.remaining_label())
+                .replace("{end}", &status.expected_end)
+                .replace("{status}", &full);
+            (text, full, class)
+        }
+        None => (String::new(), "Monitoramento parado".to_string(), "stopped"),
+    };
+
+    if options.waybar {
+        serde_json::json!({ "text": text, "tooltip": tooltip, "class": class, "alt": class }).to_string()
+    } else {
+        text
+    }
+}
+
+fn print_bar_line(status: Option<&StatusSnapshot>, options: &BarOptions) {
+    println!("{}", bar_line(status, options));
+    let _ = std::io::stdout().flush();
+}
+
+/// Acompanha o status do app pelo canal de controle até a conexão cair.
+fn watch_app(stream: TcpStream, options: &BarOptions) -> Result<(), String> {
+    let mut reader = BufReader::new(&stream);
+    loop {
+        let data = app_response(Ok(read_response(&mut reader)?))?;
+        let status: Option<StatusSnapshot> = serde_json::from_value(data)
+            .map_err(|e| format!("Invalid status: {}", e))?;
+        print_bar_line(status.as_ref(), options);
+    }
+}
+
+/// Saída contínua para barras de status. Sem o app aberto, usa o histórico
+/// local e tenta reconectar periodicamente.
+fn run_bar(options: BarOptions) -> Result<(), String> {
+    if options.once {
+        let status = match send_to_app(ControlCommand::Status) {
+            Some(response) => serde_json::from_value(app_response(response)?)
+                .map_err(|e| format!("Invalid status: {}", e))?,
+            None => {
+                let store = FileStore::open()?;
+                let times = history::read_history(&store)?.times(Local::now().date_naive());
+                standalone_work_status(&store, &times)?
+            }
+        };
+        print_bar_line(status.as_ref(), &options);
+        return Ok(());
+    }
+
+    loop {
+        if let Some(Ok(stream)) = connect_to_app(ControlCommand::Watch) {
+            if let Err(e) = watch_app(stream, &options) {
+                eprintln!("{}", e);
+            }
+        }
+
+        let store = FileStore::open()?;
+        let times = history::read_history(&store)?.times(Local::now().date_naive());
+        print_bar_line(standalone_work_status(&store, &times)?.as_ref(), &options);
+        std::thread::sleep(std::time::Duration::from_secs(BAR_RETRY_SECONDS));
+    }
+}
+
+fn parse_bar_options(args: &[String]) -> Result<BarOptions, String> {
+    let mut options = BarOptions { waybar: false, format: DEFAULT_BAR_FORMAT.to_string(), once: false };
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--waybar" => options.waybar = true,
+            "--once" => options.once = true,
+            "--format" => {
+                options.format = args.next()
+                    .ok_or_else(|| "Informe o modelo após --format".to_string())?
+                    .clone();
+            }
+            other => return Err(format!("Opção desconhecida: {}\n\n{}", other, USAGE)),
+        }
+    }
+
+    Ok(options)
+}
+
+fn save_times(store: &FileStore, times: &[NaiveTime], source: PunchSource) -> Result<(), String> {
+    let today = Local::now().date_naive();
+    let mut history = history::read_history(store)?;
+    history.set_day(today, times, source);
+
+    let format = |index: usize| times.get(index).map(|time| time.format("%H:%M").to_string()).unwrap_or_default();
+    let time_data = TimeData { inicio1: format(0), fim1: format(1), inicio2: format(2) };
+    let value = serde_json::to_value(&time_data)
+        .map_err(|e| format!("Failed to serialize time data: {}", e))?;
+    store.set("timeData", value);
+
+    history::write_history(store, &history)
+}
+
+fn standalone_start(store: &FileStore, inicio1: &str, fim1: &str, inicio2: &str) -> Result<(), String> {
+    let times = crate::parse_work_times(inicio1, fim1, inicio2)?;
+    save_times(store, &times, PunchSource::Manual)?;
+
+    let schedule = schedule::read_schedule(store)?;
+    let (_, _, expected_end) = crate::plan_workday(&schedule, Local::now().date_naive(), times);
+    if let Err(e) = ics::write_feed(store, Some(expected_end)) {
+        println!("Failed to update calendar feed: {}", e);
+    }
+    println!("Saída prevista às {}", expected_end.format("%H:%M"));
+    println!("O NoPonto não está aberto: os horários foram salvos, mas nenhum aviso será exibido.");
+    Ok(())
+}
+
+fn standalone_sync(store: &FileStore) -> Result<(), String> {
+    let config = crate::read_pontomais_config(store)?;
+    if config.is_empty() {
+        return Err("Configurações do PontoMais não encontradas. Configure primeiro na tela de configurações.".to_string());
+    }
+
+    let runtime = tokio::runtime::Runtime::new()
+        .map_err(|e| format!("Failed to start runtime: {}", e))?;
+    let today = Local::now().date_naive();
+    let punches = runtime.block_on(async {
+        let provider = provider_from_config(&config)?;
+        provider.fetch_punches(today, today).await
+    })?;
+
+    let times: Vec<NaiveTime> = punches.iter().map(|punch| punch.time).collect();
+    save_times(store, &times, PunchSource::Provider)?;
+    if let Err(e) = ics::write_feed(store, None) {
+        println!("Failed to update calendar feed: {}", e);
+    }
+
+    if times.is_empty() {
+        println!("Não foram encontrados registros de ponto para hoje.");
+    } else {
+        let formatted: Vec<String> = times.iter().map(|time| time.format("%H:%M").to_string()).collect();
+        println!("Marcações de hoje: {}", formatted.join(" "));
+    }
+    Ok(())
+}
+
+fn print_history(range: HistoryRange) -> Result<(), String> {
+    let store = FileStore::open()?;
+    let today = Local::now().date_naive();
+    let start_date = match range {
+        HistoryRange::Week => today - Duration::days(today.weekday().num_days_from_monday() as i64),
+        HistoryRange::Month => NaiveDate::from_ymd_opt(today.year(), today.month(), 1).unwrap_or(today),
+    };
+
+    let history = history::read_history(&store)?;
+    let schedule = schedule::read_schedule(&store)?;
+    let timesheet = timesheet::build_timesheet(&history, &schedule, start_date, today);
+
+    for day in &timesheet.days {
+        println!(
+            "{} {}  {:<24} trabalhado {:>6}  meta {:>5}  saldo {:>6}",
+            weekday_name(day.date),
+            day.date.format("%d/%m"),
+            day.punches.join(" "),
+            format_minutes(day.worked_minutes),
+            format_minutes(day.target_minutes),
+            format_minutes(day.balance_minutes),
+        );
+    }
+    println!(
+        "Total: trabalhado {} — meta {} — saldo {}",
+        format_minutes(timesheet.total_worked_minutes),
+        format_minutes(timesheet.total_target_minutes),
+        format_minutes(timesheet.balance_minutes),
+    );
+    Ok(())
+}
+
+fn run(args: &[String]) -> Result<(), String> {
+    let command = args.first().map(String::as_str).unwrap_or("help");
+
+    match command {
+        "status" => match send_to_app(ControlCommand::Status) {
+            Some(response) => {
+                let data = app_response(response)?;
+                print_status(serde_json::from_value(data).map_err(|e| format!("Invalid status: {}", e))?);
+                Ok(())
+            }
+            None => standalone_status(&FileStore::open()?),
+        },
+        "start" => {
+            let [inicio1, fim1, inicio2] = &args[1..] else {
+                return Err("Informe entrada, saída para o almoço e retorno (ex.: start 08:00 12:00 13:00)".to_string());
+            };
+            let command = ControlCommand::Start {
+                inicio1: inicio1.clone(),
+                fim1: fim1.clone(),
+                inicio2: inicio2.clone(),
+            };
+            match send_to_app(command) {
+                Some(response) => {
+                    let data = app_response(response)?;
+                    print_status(serde_json::from_value(data).map_err(|e| format!("Invalid status: {}", e))?);
+                    Ok(())
+                }
+                None => standalone_start(&FileStore::open()?, inicio1, fim1, inicio2),
+            }
+        }
+        "stop" => match send_to_app(ControlCommand::Stop) {
+            Some(response) => {
+                app_response(response)?;
+                println!("Monitoramento parado");
+                Ok(())
+            }
+            None => Err("O NoPonto não está em execução.".to_string()),
+        },
+        "sync" => match send_to_app(ControlCommand::Sync) {
+            Some(response) => {
+                if let serde_json::Value::String(message) = app_response(response)? {
+                    println!("{}", message);
+                }
+                Ok(())
+            }
+            None => standalone_sync(&FileStore::open()?),
+        },
+        "history" => match args.get(1).map(String::as_str) {
+            None | Some("--week") => print_history(HistoryRange::Week),
+            Some("--month") => print_history(HistoryRange::Month),
+            Some(other) => Err(format!("Opção desconhecida: {}\n\n{}", other, USAGE)),
+        },
+        "bar" => run_bar(parse_bar_options(&args[1..])?),
+        "help" | "--help" | "-h" => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        other => Err(format!("Comando desconhecido: {}\n\n{}", other, USAGE)),
+    }
+}
+
+/// Ponto de entrada do binário `noponto`. Retorna o código de saída.
+pub fn main() -> i32 {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    match run(&args) {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("{}", e);
+            1
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::status::COMPLETE_TOLERANCE_MINUTES;
+
+    fn snapshot(remaining: i64) -> StatusSnapshot {
+        let end_time = Local::now() + Duration::minutes(remaining);
+        StatusSnapshot::new(480 - remaining, remaining, 480, end_time, None, None)
+    }
+
+    fn options(args: &[&str]) -> BarOptions {
+        let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        parse_bar_options(&args).unwrap()
+    }
+
+    fn waybar_class(status: Option<&StatusSnapshot>) -> String {
+        let line: serde_json::Value = serde_json::from_str(&bar_line(status, &options(&["--waybar"]))).unwrap();
+        assert_eq!(line["class"], line["alt"]);
+        line["class"].as_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn waybar_class_follows_the_phase() {
+        assert_eq!(waybar_class(Some(&snapshot(90))), "working");
+        assert_eq!(waybar_class(Some(&snapshot(crate::WARNING_MINUTES))), "warning");
+        assert_eq!(waybar_class(Some(&snapshot(0))), "complete");
+        assert_eq!(waybar_class(Some(&snapshot(-COMPLETE_TOLERANCE_MINUTES))), "complete");
+        assert_eq!(waybar_class(Some(&snapshot(-COMPLETE_TOLERANCE_MINUTES - 1))), "overtime");
+        assert_eq!(waybar_class(None), "stopped");
+    }
+
+    #[test]
+    fn waybar_line_has_text_and_tooltip() {
+        let status = snapshot(-75);
+        let line: serde_json::Value = serde_json::from_str(&bar_line(Some(&status), &options(&["--waybar"]))).unwrap();
+        assert_eq!(line["text"], format!("+1h15 → {}", status.expected_end));
+        assert_eq!(line["tooltip"], format!("Hora extra +1h15 — saída {}", status.expected_end));
+
+        let stopped: serde_json::Value = serde_json::from_str(&bar_line(None, &options(&["--waybar"]))).unwrap();
+        assert_eq!(stopped["text"], "");
+        assert_eq!(stopped["tooltip"], "Monitoramento parado");
+    }
+
+    #[test]
+    fn custom_format_replaces_placeholders() {
+        let status = snapshot(65);
+        let options = options(&["--format", "⏱ {remaining} | {end} | {status} | {other}", "--once"]);
+        assert!(options.once);
+        assert!(!options.waybar);
+
+        assert_eq!(
+            bar_line(Some(&status), &options),
+            format!("⏱ 1h05 | {end} | Faltam 1h05 — saída {end} | {{other}}", end = status.expected_end)
+        );
+        assert_eq!(bar_line(None, &options), "");
+    }
+
+    #[test]
+    fn default_format_and_invalid_options() {
+        assert_eq!(options(&[]).format, DEFAULT_BAR_FORMAT);
+        let complete = snapshot(0);
+        assert_eq!(bar_line(Some(&complete), &options(&[])), format!("0h00 → {}", complete.expected_end));
+
+        let missing = parse_bar_options(&["--format".to_string()]).err().unwrap();
+        assert_eq!(missing, "Informe o modelo após --format");
+        let unknown = parse_bar_options(&["--polybar".to_string()]).err().unwrap();
+        assert!(unknown.starts_with("Opção desconhecida: --polybar"));
+    }
+}
@@ -0,0 +1,226 @@
+//! Canal local usado pela CLI para comandar o app em execução. O app escuta
+//! em uma porta TCP de 127.0.0.1 e grava porta e token em `control.json` na
+//! pasta de dados; cada conexão envia uma requisição JSON por linha.
+
+use aes_gcm::aead::{rand_core::RngCore, OsRng};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+#[cfg(feature = "app")]
+use tauri::{AppHandle, Manager};
+#[cfg(feature = "app")]
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+#[cfg(feature = "app")]
+use tokio::net::tcp::OwnedWriteHalf;
+#[cfg(feature = "app")]
+use tokio::net::{TcpListener, TcpStream};
+
+#[cfg(feature = "app")]
+use crate::events;
+use crate::storage;
+#[cfg(feature = "app")]
+use crate::SharedState;
+
+const CONTROL_FILE: &str = "control.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ControlEndpoint {
+    pub port: u16,
+    pub token: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "lowercase")]
+pub enum ControlCommand {
+    Status,
+    Start { inicio1: String, fim1: String, inicio2: String },
+    Stop,
+    Sync,
+    /// Mantém a conexão aberta e envia o status a cada atualização.
+    Watch,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ControlRequest {
+    pub token: String,
+    #[serde(flatten)]
+    pub command: ControlCommand,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ControlResponse {
+    pub ok: bool,
+    #[serde(default)]
+    pub data: Value,
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+impl ControlResponse {
+    fn from_result(result: Result<Value, String>) -> Self {
+        match result {
+            Ok(data) => ControlResponse { ok: true, data, error: None },
+            Err(e) => ControlResponse { ok: false, data: Value::Null, error: Some(e) },
+        }
+    }
+}
+
+/// Token hexadecimal aleatório para autenticar clientes locais.
+pub fn random_token() -> String {
+    let mut bytes = [0u8; 24];
+    OsRng.fill_bytes(&mut bytes);
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Porta e token do app em execução, se houver.
+pub fn read_endpoint() -> Option<ControlEndpoint> {
+    let path = storage::app_data_dir().ok()?.join(CONTROL_FILE);
+    let content = std::fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+pub fn remove_endpoint() {
+    if let Ok(dir) = storage::app_data_dir() {
+        let _ = std::fs::remove_file(dir.join(CONTROL_FILE));
+    }
+}
+
+#[cfg(feature = "app")]
+fn write_endpoint(endpoint: &ControlEndpoint) -> Result<(), String> {
+    use std::io::Write as _;
+
+    let dir = storage::app_data_dir()?;
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+
+    let path = dir.join(CONTROL_FILE);
+    let json = serde_json::to_string(endpoint)
+        .map_err(|e| format!("Failed to serialize control endpoint: {}", e))?;
+    // O token só deve ser legível pelo próprio usuário, desde a criação do arquivo
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(&path)
+        .map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+
+    // Um arquivo deixado por uma versão anterior pode ter outras permissões
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(std::fs::Permissions::from_mode(0o600))
+            .map_err(|e| format!("Failed to restrict {}: {}", path.display(), e))?;
+    }
+
+    file.write_all(json.as_bytes())
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+
+    Ok(())
+}
+
+#[cfg(feature = "app")]
+async fn execute(app: &AppHandle, command: ControlCommand) -> Result<Value, String> {
+    let state = app.state::<SharedState>().inner().clone();
+
+    match command {
+        // `Watch` é tratado em `handle_connection`; aqui responde como `Status`
+        ControlCommand::Status | ControlCommand::Watch => {}
+        ControlCommand::Start { inicio1, fim1, inicio2 } => {
+            // Responde com o primeiro status publicado pelo novo monitoramento
+            let mut receiver = events::subscribe(app);
+            crate::save_time_data(app, &crate::TimeData { inicio1: inicio1.clone(), fim1: fim1.clone(), inicio2: inicio2.clone() })?;
+            crate::begin_work_monitoring(app, &state, &inicio1, &fim1, &inicio2).await?;
+            while let Some(event) = events::next(&mut receiver).await {
+                if let events::WorkEvent::StatusUpdated { status } = event {
+                    return serde_json::to_value(Some(status)).map_err(|e| format!("Failed to serialize status: {}", e));
+                }
+            }
+        }
+        ControlCommand::Stop => crate::end_work_monitoring(app, &state),
+        ControlCommand::Sync => {
+            let message = crate::sync_and_start_monitoring(app).await?;
+            return Ok(Value::String(message));
+        }
+    }
+
+    serde_json::to_value(events::latest_status(app)).map_err(|e| format!("Failed to serialize status: {}", e))
+}
+
+#[cfg(feature = "app")]
+async fn write_response(writer: &mut OwnedWriteHalf, response: &ControlResponse) -> bool {
+    let Ok(mut json) = serde_json::to_string(response) else { return false };
+    json.push('\n');
+    writer.write_all(json.as_bytes()).await.is_ok()
+}
+
+#[cfg(feature = "app")]
+/// Envia o status atual e depois cada atualização, até o cliente desconectar.
+async fn watch_status(app: &AppHandle, writer: &mut OwnedWriteHalf) {
+    let mut receiver = events::subscribe(app);
+
+    loop {
+        let data = serde_json::to_value(events::latest_status(app)).unwrap_or(Value::Null);
+        if !write_response(writer, &ControlResponse::from_result(Ok(data))).await {
+            return;
+        }
+
+        loop {
+            match events::next(&mut receiver).await {
+                Some(event) if event.changes_status() => break,
+                Some(_) => continue,
+                None => return,
+            }
+        }
+    }
+}
+
+#[cfg(feature = "app")]
+async fn handle_connection(app: AppHandle, token: String, stream: TcpStream) {
+    let (reader, mut writer) = stream.into_split();
+    let mut lines = BufReader::new(reader).lines();
+
+    while let Ok(Some(line)) = lines.next_line().await {
+        let response = match serde_json::from_str::<ControlRequest>(&line) {
+            Ok(request) if request.token == token => {
+                if let ControlCommand::Watch = request.command {
+                    watch_status(&app, &mut writer).await;
+                    break;
+                }
+                ControlResponse::from_result(execute(&app, request.command).await)
+            }
+            Ok(_) => ControlResponse::from_result(Err("Token inválido".to_string())),
+            Err(e) => ControlResponse::from_result(Err(format!("Invalid request: {}", e))),
+        };
+
+        if !write_response(&mut writer, &response).await {
+            break;
+        }
+    }
+}
+
+#[cfg(feature = "app")]
+/// Abre o canal de controle e atende conexões até o app encerrar.
+pub async fn serve(app: AppHandle) -> Result<(), String> {
+    let listener = TcpListener::bind(("127.0.0.1", 0))
+        .await
+        .map_err(|e| format!("Failed to bind control socket: {}", e))?;
+    let port = listener.local_addr()
+        .map_err(|e| format!("Failed to read control socket address: {}", e))?
+        .port();
+
+    let token = random_token();
+
+    write_endpoint(&ControlEndpoint { port, token: token.clone() })?;
+    println!("Control socket listening on 127.0.0.1:{}", port);
+
+    loop {
+        match listener.accept().await {
+            Ok((stream, _)) => {
+                tokio::spawn(handle_connection(app.clone(), token.clone(), stream));
+            }
+            Err(e) => println!("Failed to accept control connection: {}", e),
+        }
+    }
+}
@@ -0,0 +1,129 @@
+//! Serviço D-Bus de sessão (Linux) para extensões do GNOME e widgets do KDE.
+//! Expõe o status do monitoramento como propriedades, com `PropertiesChanged`
+//! a cada atualização, e métodos para iniciar, parar e sincronizar.
+//!
+//! Para testar com um barramento privado:
+//! `dbus-run-session -- sh -c 'NoPonto & sleep 2; busctl --user introspect io.github.gabrielgriffo.NoPonto /io/github/gabrielgriffo/NoPonto'`
+
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+use zbus::{connection, fdo, interface, Connection};
+
+use crate::events;
+use crate::{SharedState, WorkStatus};
+
+const SERVICE_NAME: &str = "io.github.gabrielgriffo.NoPonto";
+const OBJECT_PATH: &str = "/io/github/gabrielgriffo/NoPonto";
+
+/// Conexão com o barramento, guardada para emitir os sinais de mudança.
+#[derive(Default)]
+pub struct DbusService {
+    connection: Mutex<Option<Connection>>,
+}
+
+struct NoPontoInterface {
+    app: AppHandle,
+}
+
+impl NoPontoInterface {
+    fn status(&self) -> Option<WorkStatus> {
+        self.app.state::<SharedState>().lock().unwrap().clone()
+    }
+}
+
+#[interface(name = "io.github.gabrielgriffo.NoPonto1")]
+impl NoPontoInterface {
+    #[zbus(property)]
+    fn monitoring(&self) -> bool {
+        self.status().is_some()
+    }
+
+    /// Negativo em hora extra.
+    #[zbus(property)]
+    fn remaining_minutes(&self) -> i64 {
+        self.status().map(|status| status.remaining_minutes).unwrap_or(0)
+    }
+
+    /// Saída prevista no formato `HH:MM`; vazio sem monitoramento.
+    #[zbus(property)]
+    fn expected_end(&self) -> String {
+        self.status().map(|status| status.end_time).unwrap_or_default()
+    }
+
+    #[zbus(property)]
+    fn is_complete(&self) -> bool {
+        self.status().is_some_and(|status| status.is_complete)
+    }
+
+    async fn start(&self, inicio1: String, fim1: String, inicio2: String) -> fdo::Result<()> {
+        let state = self.app.state::<SharedState>().inner().clone();
+        let times = crate::TimeData { inicio1, fim1, inicio2 };
+        crate::save_time_data(&self.app, &times).map_err(fdo::Error::Failed)?;
+        crate::begin_work_monitoring(&self.app, &state, &times.inicio1, &times.fim1, &times.inicio2)
+            .await
+            .map_err(fdo::Error::InvalidArgs)
+    }
+
+    fn stop(&self) {
+        let state = self.app.state::<SharedState>().inner().clone();
+        crate::end_work_monitoring(&self.app, &state);
+    }
+
+    async fn sync(&self) -> fdo::Result<String> {
+        crate::sync_and_start_monitoring(&self.app).await.map_err(fdo::Error::Failed)
+    }
+}
+
+/// Registra o serviço no barramento de sessão.
+pub async fn serve(app: AppHandle) -> Result<(), String> {
+    let interface = NoPontoInterface { app: app.clone() };
+
+    let connection = connection::Builder::session()
+        .and_then(|builder| builder.name(SERVICE_NAME))
+        .and_then(|builder| builder.serve_at(OBJECT_PATH, interface))
+        .map_err(|e| format!("Failed to configure D-Bus service: {}", e))?
+        .build()
+        .await
+        .map_err(|e| format!("Failed to connect to the session bus: {}", e))?;
+
+    println!("D-Bus service registered as {}", SERVICE_NAME);
+    *app.state::<DbusService>().connection.lock().unwrap() = Some(connection);
+    Ok(())
+}
+
+/// Libera o nome no barramento e fecha a conexão ao encerrar o app.
+pub async fn stop(app: &AppHandle) {
+    let connection = app.state::<DbusService>().connection.lock().unwrap().take();
+    if let Some(connection) = connection {
+        if let Err(e) = connection.close().await {
+            println!("Failed to close D-Bus connection: {}", e);
+        }
+    }
+}
+
+/// Emite `PropertiesChanged` com o status atual.
+fn status_changed(app: &AppHandle) {
+    let Some(connection) = app.state::<DbusService>().connection.lock().unwrap().clone() else { return };
+
+    tauri::async_runtime::spawn(async move {
+        let Ok(interface) = connection.object_server().interface::<_, NoPontoInterface>(OBJECT_PATH).await else {
+            return;
+        };
+        let emitter = interface.signal_emitter();
+        let interface = interface.get().await;
+
+        let _ = interface.monitoring_changed(emitter).await;
+        let _ = interface.remaining_minutes_changed(emitter).await;
+        let _ = interface.expected_end_changed(emitter).await;
+        let _ = interface.is_complete_changed(emitter).await;
+    });
+}
+
+/// Assina o barramento de eventos e emite `PropertiesChanged` a cada mudança de status.
+pub fn listen(app: &AppHandle) {
+    events::listen(app, |app, event| {
+        if event.changes_status() {
+            status_changed(app);
+        }
+    });
+}
@@ -0,0 +1,389 @@
+//! Resumos por e-mail via SMTP: fechamento do dia (marcações, trabalhado e
+//! saldo) e relatório semanal. As configurações, inclusive a senha, ficam
+//! criptografadas no store como as do PontoMais.
+
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, NaiveTime};
+use lettre::message::{header::ContentType, Mailbox};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_store::StoreExt;
+
+use crate::timesheet::{self, format_minutes, weekday_name};
+use crate::{history, report, schedule};
+
+const SETTINGS_KEY: &str = "email_settings";
+const LAST_SENT_KEY: &str = "email_last_sent";
+/// Exibido no lugar da senha salva; ao salvar, mantém a senha atual.
+const PASSWORD_MASK: &str = "********";
+/// Falhas seguidas antes de desistir do resumo no dia.
+const MAX_SEND_ATTEMPTS: u32 = 4;
+/// Espera após a primeira falha, dobrando a cada nova falha.
+const RETRY_BASE_MINUTES: i64 = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SmtpSecurity {
+    /// Sem criptografia (ex.: MailHog na porta 1025).
+    None,
+    StartTls,
+    /// TLS implícito, normalmente na porta 465.
+    Tls,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SummaryKind {
+    Daily,
+    Weekly,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmailSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    pub host: String,
+    pub port: u16,
+    pub security: SmtpSecurity,
+    #[serde(default)]
+    pub username: String,
+    #[serde(default)]
+    pub password: String,
+    pub from: String,
+    pub to: Vec<String>,
+    #[serde(default = "default_true")]
+    pub daily_summary: bool,
+    #[serde(default = "default_true")]
+    pub weekly_report: bool,
+    /// Horário de envio dos resumos (`HH:MM`).
+    #[serde(default = "default_send_at")]
+    pub send_at: String,
+    /// Dia do relatório semanal, de 1 (segunda) a 7 (domingo).
+    #[serde(default = "default_weekly_day")]
+    pub weekly_day: u32,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_send_at() -> String {
+    "18:30".to_string()
+}
+
+fn default_weekly_day() -> u32 {
+    5
+}
+
+/// Datas dos últimos envios, para não repetir um resumo já enviado.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct LastSent {
+    daily: Option<NaiveDate>,
+    weekly: Option<NaiveDate>,
+}
+
+/// Falhas de envio de um resumo no dia, para esperar entre as tentativas em
+/// vez de insistir a cada minuto.
+#[derive(Debug, Default)]
+struct RetryState {
+    date: Option<NaiveDate>,
+    failures: u32,
+    next_attempt: Option<DateTime<Local>>,
+}
+
+impl RetryState {
+    fn ready(&mut self, now: DateTime<Local>) -> bool {
+        if self.date != Some(now.date_naive()) {
+            *self = RetryState { date: Some(now.date_naive()), ..Default::default() };
+        }
+        self.failures < MAX_SEND_ATTEMPTS && self.next_attempt.is_none_or(|at| now >= at)
+    }
+
+    fn failed(&mut self, now: DateTime<Local>) {
+        self.failures += 1;
+        self.next_attempt = Some(now + Duration::minutes(RETRY_BASE_MINUTES << (self.failures - 1)));
+    }
+}
+
+#[derive(Debug, Default)]
+struct Retries {
+    daily: RetryState,
+    weekly: RetryState,
+}
+
+pub fn load_email_settings(app: &AppHandle) -> Result<Option<EmailSettings>, String> {
+    let store = app.store("noponto.dat")
+        .map_err(|e| format!("Failed to get store: {}", e))?;
+
+    let Some(encrypted) = store.get(SETTINGS_KEY) else { return Ok(None) };
+    let Some(encrypted) = encrypted.as_str() else { return Ok(None) };
+
+    let json = crate::decrypt_data(encrypted)?;
+    serde_json::from_str(&json)
+        .map(Some)
+        .map_err(|e| format!("Failed to parse email settings: {}", e))
+}
+
+fn load_last_sent(app: &AppHandle) -> LastSent {
+    app.store("noponto.dat")
+        .ok()
+        .and_then(|store| store.get(LAST_SENT_KEY))
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+fn save_last_sent(app: &AppHandle, last_sent: &LastSent) -> Result<(), String> {
+    let store = app.store("noponto.dat")
+        .map_err(|e| format!("Failed to get store: {}", e))?;
+    let value = serde_json::to_value(last_sent)
+        .map_err(|e| format!("Failed to serialize email state: {}", e))?;
+    store.set(LAST_SENT_KEY, value);
+    store.save()
+        .map_err(|e| format!("Failed to save store: {}", e))
+}
+
+async fn send_email(settings: &EmailSettings, subject: &str, body: String) -> Result<(), String> {
+    let from: Mailbox = settings.from.parse()
+        .map_err(|e| format!("Remetente inválido {}: {}", settings.from, e))?;
+    let mut builder = Message::builder()
+        .from(from)
+        .subject(subject)
+        .header(ContentType::TEXT_PLAIN);
+    for to in &settings.to {
+        let mailbox: Mailbox = to.parse()
+            .map_err(|e| format!("Destinatário inválido {}: {}", to, e))?;
+        builder = builder.to(mailbox);
+    }
+    let message = builder.body(body)
+        .map_err(|e| format!("Failed to build email: {}", e))?;
+
+    let mut transport = match settings.security {
+        SmtpSecurity::None => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&settings.host),
+        SmtpSecurity::StartTls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&settings.host)
+            .map_err(|e| format!("Failed to configure SMTP: {}", e))?,
+        SmtpSecurity::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(&settings.host)
+            .map_err(|e| format!("Failed to configure SMTP: {}", e))?,
+    }
+    .port(settings.port);
+    if !settings.username.is_empty() {
+        transport = transport.credentials(Credentials::new(settings.username.clone(), settings.password.clone()));
+    }
+
+    transport.build().send(message).await
+        .map_err(|e| format!("Failed to send email: {}", e))?;
+    Ok(())
+}
+
+/// Resumo do dia; `None` em fins de semana, feriados, folgas e dias sem marcações.
+fn daily_summary(app: &AppHandle, date: NaiveDate) -> Result<Option<(String, String)>, String> {
+    let history = history::load_history(app)?;
+    let schedule = schedule::load_schedule(app)?;
+    if schedule.day_plan(date, &[]).day_off.is_some() {
+        return Ok(None);
+    }
+
+    let timesheet = timesheet::build_timesheet(&history, &schedule, date, date);
+    let Some(day) = timesheet.days.first().filter(|day| !day.punches.is_empty()) else {
+        return Ok(None);
+    };
+
+    let punches = day.punches.join(" ");
+    let subject = format!("NoPonto — resumo de {} ({})", date.format("%d/%m/%Y"), weekday_name(date));
+    let body = format!(
+        "Resumo do dia {}\n\nMarcações: {}\nTrabalhado: {}\nMeta: {}\nSaldo do dia: {}\n",
+        date.format("%d/%m/%Y"),
+        punches,
+        format_minutes(day.worked_minutes),
+        format_minutes(day.target_minutes),
+        format_minutes(day.balance_minutes),
+    );
+    Ok(Some((subject, body)))
+}
+
+/// Relatório da semana (segunda até `date`).
+fn weekly_report(app: &AppHandle, date: NaiveDate) -> Result<(String, String), String> {
+    let monday = date - Duration::days(date.weekday().num_days_from_monday() as i64);
+    let history = history::load_history(app)?;
+    let schedule = schedule::load_schedule(app)?;
+    let timesheet = timesheet::build_timesheet(&history, &schedule, monday, date);
+
+    let mut body = format!("Semana de {} a {}\n\n", monday.format("%d/%m"), date.format("%d/%m/%Y"));
+    for day in &timesheet.days {
+        body.push_str(&format!(
+            "{} {}  {:<24} trabalhado {:>6}  saldo {:>6}\n",
+            weekday_name(day.date),
+            day.date.format("%d/%m"),
+            day.punches.join(" "),
+            format_minutes(day.worked_minutes),
+            format_minutes(day.balance_minutes),
+        ));
+    }
+
+    let report = report::period_report(&history, String::new(), &timesheet.days);
+    body.push_str(&format!(
+        "\nTrabalhado: {}\nMeta: {}\nSaldo: {}\nHoras extras: {}\n",
+        format_minutes(report.worked_minutes),
+        format_minutes(report.target_minutes),
+        format_minutes(report.balance_minutes),
+        format_minutes(report.overtime_minutes),
+    ));
+    if let (Some(start), Some(end)) = (&report.average_start, &report.average_end) {
+        body.push_str(&format!("Entrada média: {} — saída média: {}\n", start, end));
+    }
+    if !report.violation_days.is_empty() {
+        body.push_str("\nAlertas:\n");
+        for day in &report.violation_days {
+            let descriptions: Vec<&str> = day.violations.iter().map(|violation| violation.description()).collect();
+            body.push_str(&format!("- {}: {}\n", day.date.format("%d/%m"), descriptions.join(", ")));
+        }
+    }
+
+    let subject = format!("NoPonto — relatório da semana de {}", monday.format("%d/%m/%Y"));
+    Ok((subject, body))
+}
+
+/// Envia o resumo. `Ok(false)` quando não há o que enviar no dia.
+async fn send_summary(app: &AppHandle, settings: &EmailSettings, kind: SummaryKind, date: NaiveDate) -> Result<bool, String> {
+    let summary = match kind {
+        SummaryKind::Daily => daily_summary(app, date)?,
+        SummaryKind::Weekly => Some(weekly_report(app, date)?),
+    };
+    let Some((subject, body)) = summary else { return Ok(false) };
+
+    send_email(settings, &subject, body).await?;
+    Ok(true)
+}
+
+/// Envia um resumo pendente, respeitando a espera após falhas. Um resumo sem
+/// conteúdo conta como enviado, para não ser verificado de novo no dia.
+async fn send_due(app: &AppHandle, settings: &EmailSettings, kind: SummaryKind, retry: &mut RetryState) -> Result<(), String> {
+    let now = Local::now();
+    if !retry.ready(now) {
+        return Ok(());
+    }
+
+    if let Err(e) = send_summary(app, settings, kind, now.date_naive()).await {
+        retry.failed(now);
+        if retry.failures >= MAX_SEND_ATTEMPTS {
+            return Err(format!("{} (giving up for today)", e));
+        }
+        return Err(e);
+    }
+
+    let mut last_sent = load_last_sent(app);
+    match kind {
+        SummaryKind::Daily => last_sent.daily = Some(now.date_naive()),
+        SummaryKind::Weekly => last_sent.weekly = Some(now.date_naive()),
+    }
+    save_last_sent(app, &last_sent)
+}
+
+/// Envia os resumos pendentes quando chega o horário configurado. Uma falha
+/// em um resumo não impede o envio do outro.
+async fn send_due_summaries(app: &AppHandle, retries: &mut Retries) -> Result<(), String> {
+    let Some(settings) = load_email_settings(app)? else { return Ok(()) };
+    if !settings.enabled {
+        return Ok(());
+    }
+
+    let send_at = NaiveTime::parse_from_str(&settings.send_at, "%H:%M")
+        .map_err(|e| format!("Horário de envio inválido {}: {}", settings.send_at, e))?;
+    let now = Local::now();
+    let today = now.date_naive();
+    if now.time() < send_at {
+        return Ok(());
+    }
+
+    let last_sent = load_last_sent(app);
+
+    if settings.daily_summary && last_sent.daily != Some(today) {
+        if let Err(e) = send_due(app, &settings, SummaryKind::Daily, &mut retries.daily).await {
+            println!("Failed to send daily email summary: {}", e);
+        }
+    }
+
+    if settings.weekly_report && today.weekday().number_from_monday() == settings.weekly_day && last_sent.weekly != Some(today) {
+        if let Err(e) = send_due(app, &settings, SummaryKind::Weekly, &mut retries.weekly).await {
+            println!("Failed to send weekly email report: {}", e);
+        }
+    }
+
+    Ok(())
+}
+
+/// Tarefa do agendador, gerenciada pelo Tauri para ser parada ao encerrar.
+#[derive(Default)]
+pub struct EmailScheduler {
+    task: Mutex<Option<tauri::async_runtime::JoinHandle<()>>>,
+}
+
+/// Verifica a cada minuto se há resumos a enviar, até `stop_scheduler`.
+async fn run_scheduler(app: AppHandle) {
+    let mut retries = Retries::default();
+    loop {
+        if let Err(e) = send_due_summaries(&app, &mut retries).await {
+            println!("Failed to send email summary: {}", e);
+        }
+        tokio::time::sleep(std::time::Duration::from_secs(60)).await;
+    }
+}
+
+pub fn start_scheduler(app: &AppHandle) {
+    let task = tauri::async_runtime::spawn(run_scheduler(app.clone()));
+    *app.state::<EmailScheduler>().task.lock().unwrap() = Some(task);
+}
+
+pub fn stop_scheduler(app: &AppHandle) {
+    if let Some(task) = app.state::<EmailScheduler>().task.lock().unwrap().take() {
+        task.abort();
+    }
+}
+
+/// Configurações com a senha mascarada; ela só sai do store para o envio.
+#[tauri::command]
+pub async fn get_email_settings(app: AppHandle) -> Result<Option<EmailSettings>, String> {
+    let mut settings = load_email_settings(&app)?;
+    if let Some(settings) = settings.as_mut().filter(|settings| !settings.password.is_empty()) {
+        settings.password = PASSWORD_MASK.to_string();
+    }
+    Ok(settings)
+}
+
+/// Salva as configurações. A senha mascarada recebida de `get_email_settings`
+/// mantém a senha já salva.
+#[tauri::command]
+pub async fn save_email_settings(app: AppHandle, mut settings: EmailSettings) -> Result<(), String> {
+    NaiveTime::parse_from_str(&settings.send_at, "%H:%M")
+        .map_err(|_| format!("Horário de envio inválido: {}", settings.send_at))?;
+    if !(1..=7).contains(&settings.weekly_day) {
+        return Err("O dia do relatório semanal deve estar entre 1 (segunda) e 7 (domingo)".to_string());
+    }
+    if settings.password == PASSWORD_MASK {
+        settings.password = load_email_settings(&app)?.map(|saved| saved.password).unwrap_or_default();
+    }
+
+    let json = serde_json::to_string(&settings)
+        .map_err(|e| format!("Failed to serialize email settings: {}", e))?;
+    let encrypted = crate::encrypt_data(&json)?;
+
+    let store = app.store("noponto.dat")
+        .map_err(|e| format!("Failed to get store: {}", e))?;
+    store.set(SETTINGS_KEY, serde_json::Value::String(encrypted));
+
+    store.save()
+        .map_err(|e| format!("Failed to save store: {}", e))
+}
+
+/// Envia agora o resumo de hoje ou o relatório da semana atual.
+#[tauri::command]
+pub async fn send_test_email(app: AppHandle, kind: SummaryKind) -> Result<String, String> {
+    let settings = load_email_settings(&app)?
+        .ok_or_else(|| "Configurações de e-mail não encontradas.".to_string())?;
+
+    if !send_summary(&app, &settings, kind, Local::now().date_naive()).await? {
+        return Err("Sem resumo para hoje: fim de semana, feriado, folga ou dia sem marcações.".to_string());
+    }
+    Ok(format!("E-mail enviado para {}", settings.to.join(", ")))
+}
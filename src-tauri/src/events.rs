@@ -0,0 +1,187 @@
+//! Barramento interno de eventos do monitoramento. O monitor só publica
+//! `WorkEvent`s; bandeja, notificações, interface e canais externos assinam o
+//! barramento de forma independente, cada um em sua própria tarefa.
+
+use chrono::{DateTime, Local};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::sync::broadcast;
+
+use crate::schedule::Reminder;
+use crate::status::{format_snooze, StatusSnapshot};
+use crate::{MonitorControl, TimeData};
+
+#[derive(Debug, Clone)]
+pub enum WorkEvent {
+    /// Monitoramento iniciado (ou reiniciado) com os horários do dia.
+    MonitoringStarted {
+        times: TimeData,
+        end_time: DateTime<Local>,
+        /// Motivo da folga, quando hoje não é dia de trabalho.
+        day_off: Option<String>,
+        /// Primeira vez que esta saída prevista é anunciada hoje.
+        announce: bool,
+    },
+    /// Atualização de cada minuto, com o status já calculado.
+    StatusUpdated { status: StatusSnapshot },
+    /// Lembrete de compromisso importado do calendário.
+    Reminder { title: String, message: String },
+    /// Repetido a cada minuto nos minutos finais; `first` marca o primeiro aviso.
+    AlmostComplete {
+        remaining_minutes: i64,
+        end_time: DateTime<Local>,
+        first: bool,
+    },
+    Complete { end_time: DateTime<Local> },
+    /// Cada hora cheia de hora extra.
+    Overtime {
+        overtime_minutes: i64,
+        end_time: DateTime<Local>,
+    },
+    MonitoringStopped,
+    SyncFailed { error: String },
+    /// Notificações silenciadas até `until`, ou reativadas (`None`).
+    SnoozeChanged { until: Option<DateTime<Local>> },
+}
+
+impl WorkEvent {
+    /// Se o evento altera o `WorkStatus` compartilhado.
+    pub fn changes_status(&self) -> bool {
+        matches!(
+            self,
+            WorkEvent::MonitoringStarted { .. } | WorkEvent::StatusUpdated { .. } | WorkEvent::MonitoringStopped
+        )
+    }
+}
+
+/// Fim do silêncio em andamento, se houver.
+fn active_snooze(app: &AppHandle) -> Option<DateTime<Local>> {
+    let until = *app.state::<MonitorControl>().snoozed_until.lock().unwrap();
+    until.filter(|until| Local::now() < *until)
+}
+
+pub struct EventBus {
+    sender: broadcast::Sender<WorkEvent>,
+    /// Último status publicado, para a janela que abre depois e os canais que
+    /// publicam o status atual.
+    latest_status: Mutex<Option<StatusSnapshot>>,
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        EventBus {
+            sender: broadcast::channel(64).0,
+            latest_status: Mutex::new(None),
+        }
+    }
+}
+
+pub fn publish(app: &AppHandle, event: WorkEvent) {
+    if let Some(bus) = app.try_state::<EventBus>() {
+        // Atualizado antes do envio, para que os assinantes já leiam o status novo
+        let mut latest = bus.latest_status.lock().unwrap();
+        match &event {
+            WorkEvent::StatusUpdated { status } => *latest = Some(status.clone()),
+            WorkEvent::SnoozeChanged { until } => {
+                if let Some(status) = latest.as_mut() {
+                    status.snoozed_until = format_snooze(*until);
+                }
+            }
+            WorkEvent::MonitoringStopped => *latest = None,
+            _ => {}
+        }
+        drop(latest);
+        // Sem assinantes o envio falha, o que não é um erro
+        let _ = bus.sender.send(event);
+    }
+}
+
+/// Calcula o status uma única vez e o publica em `StatusUpdated`.
+/// `remaining_minutes` fica negativo em hora extra.
+pub fn publish_status(
+    app: &AppHandle,
+    worked_minutes: i64,
+    remaining_minutes: i64,
+    target_minutes: i64,
+    end_time: DateTime<Local>,
+    next_reminder: Option<&Reminder>,
+) {
+    let status = StatusSnapshot::new(
+        worked_minutes,
+        remaining_minutes,
+        target_minutes,
+        end_time,
+        next_reminder,
+        active_snooze(app),
+    );
+    publish(app, WorkEvent::StatusUpdated { status });
+}
+
+/// Último status publicado; `None` sem monitoramento.
+pub fn latest_status(app: &AppHandle) -> Option<StatusSnapshot> {
+    app.try_state::<EventBus>()
+        .and_then(|bus| bus.latest_status.lock().unwrap().clone())
+}
+
+pub fn subscribe(app: &AppHandle) -> broadcast::Receiver<WorkEvent> {
+    app.state::<EventBus>().sender.subscribe()
+}
+
+/// Próximo evento, ignorando os perdidos por atraso do assinante. `None`
+/// quando o barramento é encerrado.
+pub async fn next(receiver: &mut broadcast::Receiver<WorkEvent>) -> Option<WorkEvent> {
+    loop {
+        match receiver.recv().await {
+            Ok(event) => return Some(event),
+            Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                println!("Event listener lagged behind, {} event(s) skipped", skipped);
+            }
+            Err(broadcast::error::RecvError::Closed) => return None,
+        }
+    }
+}
+
+/// Assina o barramento e chama `handler` para cada evento em uma tarefa
+/// própria. A assinatura é feita antes de retornar, então nenhum evento
+/// publicado depois desta chamada é perdido.
+pub fn listen<F>(app: &AppHandle, mut handler: F)
+where
+    F: FnMut(&AppHandle, WorkEvent) + Send + 'static,
+{
+    let mut receiver = subscribe(app);
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        while let Some(event) = next(&mut receiver).await {
+            handler(&app, event);
+        }
+    });
+}
+
+/// Repassa os eventos à interface com os nomes que o frontend já escuta. A
+/// cada mudança de status envia também `work_status_updated` com o status
+/// completo (`null` sem monitoramento).
+pub fn forward_to_frontend(app: &AppHandle) {
+    listen(app, |app, event| {
+        let _ = match event {
+            WorkEvent::MonitoringStarted { times, .. } => app.emit("monitoring_started", times),
+            WorkEvent::StatusUpdated { status } => app.emit("work_status_updated", Some(status)),
+            WorkEvent::SnoozeChanged { .. } => match latest_status(app) {
+                Some(status) => app.emit("work_status_updated", Some(status)),
+                None => Ok(()),
+            },
+            WorkEvent::MonitoringStopped => {
+                let _ = app.emit("work_status_updated", None::<StatusSnapshot>);
+                app.emit("monitoring_stopped", ())
+            }
+            WorkEvent::AlmostComplete { remaining_minutes, .. } => app.emit("work_almost_complete", remaining_minutes),
+            WorkEvent::Complete { .. } => app.emit("work_complete", ()),
+            _ => Ok(()),
+        };
+    });
+}
+
+/// Status completo atual, usado pela interface ao abrir.
+#[tauri::command]
+pub fn get_status_snapshot(bus: State<'_, EventBus>) -> Option<StatusSnapshot> {
+    bus.latest_status.lock().unwrap().clone()
+}
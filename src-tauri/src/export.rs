@@ -0,0 +1,295 @@
+use chrono::{Local, NaiveDate};
+use printpdf::{BuiltinFont, IndirectFontRef, Line, Mm, PdfDocument, PdfLayerReference, Point};
+use serde::Deserialize;
+use std::fs::File;
+use std::io::BufWriter;
+use tauri::AppHandle;
+
+use crate::history;
+use crate::schedule;
+use crate::timesheet::{self, format_minutes, weekday_name, Timesheet};
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Csv,
+    Json,
+    Pdf,
+}
+
+fn timesheet_csv(timesheet: &Timesheet) -> String {
+    let mut csv = String::from("data;dia;marcacoes;trabalhado;meta;extra;adicional;saldo;banco_de_horas\n");
+
+    for day in &timesheet.days {
+        csv.push_str(&format!(
+            "{};{};{};{};{};{};{}%;{};{}\n",
+            day.date.format("%d/%m/%Y"),
+            weekday_name(day.date),
+            day.punches.join(" "),
+            format_minutes(day.worked_minutes),
+            format_minutes(day.target_minutes),
+            format_minutes(day.overtime_minutes),
+            day.overtime_rate,
+            format_minutes(day.balance_minutes),
+            format_minutes(day.bank_minutes),
+        ));
+    }
+
+    csv.push_str(&format!(
+        "total;;;{};{};{};;{};{}\n",
+        format_minutes(timesheet.total_worked_minutes),
+        format_minutes(timesheet.total_target_minutes),
+        format_minutes(timesheet.total_overtime_minutes),
+        format_minutes(timesheet.balance_minutes),
+        format_minutes(timesheet.balance_minutes),
+    ));
+
+    csv
+}
+
+fn timesheet_json(timesheet: &Timesheet) -> Result<String, String> {
+    serde_json::to_string_pretty(timesheet)
+        .map_err(|e| format!("Failed to serialize timesheet: {}", e))
+}
+
+const PAGE_WIDTH: f32 = 210.0;
+const PAGE_HEIGHT: f32 = 297.0;
+const MARGIN: f32 = 15.0;
+const ROW_HEIGHT: f32 = 6.0;
+/// Espaço reservado no fim da última página para totais e assinaturas.
+const FOOTER_HEIGHT: f32 = 60.0;
+
+const COLUMNS: [(&str, f32); 8] = [
+    ("Data", MARGIN),
+    ("Dia", 38.0),
+    ("Marcações", 50.0),
+    ("Trabalhado", 102.0),
+    ("Meta", 123.0),
+    ("Extra", 140.0),
+    ("Saldo", 157.0),
+    ("Banco", 176.0),
+];
+
+fn horizontal_line(layer: &PdfLayerReference, y: f32, from: f32, to: f32) {
+    layer.add_line(Line {
+        points: vec![(Point::new(Mm(from), Mm(y)), false), (Point::new(Mm(to), Mm(y)), false)],
+        is_closed: false,
+    });
+}
+
+fn table_header(layer: &PdfLayerReference, font: &IndirectFontRef, y: f32) {
+    for (title, x) in COLUMNS {
+        layer.use_text(title, 9.0, Mm(x), Mm(y), font);
+    }
+    horizontal_line(layer, y - 2.0, MARGIN, PAGE_WIDTH - MARGIN);
+}
+
+/// Gera o "espelho de ponto" em PDF, com uma linha por dia, totais e
+/// campos para assinatura do empregado e do gestor.
+fn write_timesheet_pdf(timesheet: &Timesheet, employee_name: &str, path: &str) -> Result<(), String> {
+    let title = format!(
+        "Espelho de Ponto - {} a {}",
+        timesheet.start_date.format("%d/%m/%Y"),
+        timesheet.end_date.format("%d/%m/%Y")
+    );
+
+    let (doc, first_page, first_layer) = PdfDocument::new(&title, Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Espelho");
+    let regular = doc.add_builtin_font(BuiltinFont::Helvetica)
+        .map_err(|e| format!("Failed to load PDF font: {}", e))?;
+    let bold = doc.add_builtin_font(BuiltinFont::HelveticaBold)
+        .map_err(|e| format!("Failed to load PDF font: {}", e))?;
+
+    let mut layer = doc.get_page(first_page).get_layer(first_layer);
+    let mut y = PAGE_HEIGHT - MARGIN - 5.0;
+
+    layer.use_text("Espelho de Ponto", 16.0, Mm(MARGIN), Mm(y), &bold);
+    y -= 8.0;
+    layer.use_text(
+        format!(
+            "Período: {} a {}",
+            timesheet.start_date.format("%d/%m/%Y"),
+            timesheet.end_date.format("%d/%m/%Y")
+        ),
+        10.0,
+        Mm(MARGIN),
+        Mm(y),
+        &regular,
+    );
+    if !employee_name.is_empty() {
+        y -= 5.0;
+        layer.use_text(format!("Empregado: {}", employee_name), 10.0, Mm(MARGIN), Mm(y), &regular);
+    }
+    y -= 10.0;
+    table_header(&layer, &bold, y);
+    y -= ROW_HEIGHT + 1.0;
+
+    for (index, day) in timesheet.days.iter().enumerate() {
+        let is_last = index + 1 == timesheet.days.len();
+        let min_y = if is_last { MARGIN + FOOTER_HEIGHT } else { MARGIN };
+        if y < min_y {
+            let (page, page_layer) = doc.add_page(Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Espelho");
+            layer = doc.get_page(page).get_layer(page_layer);
+            y = PAGE_HEIGHT - MARGIN - 5.0;
+            table_header(&layer, &bold, y);
+            y -= ROW_HEIGHT + 1.0;
+        }
+
+        let values = [
+            day.date.format("%d/%m/%Y").to_string(),
+            weekday_name(day.date).to_string(),
+            day.punches.join("  "),
+            format_minutes(day.worked_minutes),
+            format_minutes(day.target_minutes),
+            format_minutes(day.overtime_minutes),
+            format_minutes(day.balance_minutes),
+            format_minutes(day.bank_minutes),
+        ];
+        for (value, (_, x)) in values.iter().zip(COLUMNS) {
+            layer.use_text(value.as_str(), 9.0, Mm(x), Mm(y), &regular);
+        }
+        y -= ROW_HEIGHT;
+    }
+
+    if y < MARGIN + FOOTER_HEIGHT {
+        let (page, page_layer) = doc.add_page(Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Espelho");
+        layer = doc.get_page(page).get_layer(page_layer);
+        y = PAGE_HEIGHT - MARGIN - 5.0;
+    }
+
+    horizontal_line(&layer, y + 3.0, MARGIN, PAGE_WIDTH - MARGIN);
+    y -= 4.0;
+    let totals = [
+        format!("Total trabalhado: {}", format_minutes(timesheet.total_worked_minutes)),
+        format!("Meta do período: {}", format_minutes(timesheet.total_target_minutes)),
+        format!(
+            "Horas extras: {} (100%: {})",
+            format_minutes(timesheet.total_overtime_minutes),
+            format_minutes(timesheet.total_overtime_100_minutes)
+        ),
+        format!("Banco de horas: {}", format_minutes(timesheet.balance_minutes)),
+    ];
+    for total in totals {
+        layer.use_text(total, 10.0, Mm(MARGIN), Mm(y), &bold);
+        y -= 5.5;
+    }
+
+    let signature_y = MARGIN + 12.0;
+    horizontal_line(&layer, signature_y, MARGIN, 95.0);
+    horizontal_line(&layer, signature_y, 115.0, PAGE_WIDTH - MARGIN);
+    layer.use_text("Assinatura do empregado", 9.0, Mm(MARGIN), Mm(signature_y - 5.0), &regular);
+    layer.use_text("Assinatura do gestor", 9.0, Mm(115.0), Mm(signature_y - 5.0), &regular);
+    layer.use_text(
+        format!("Gerado pelo NoPonto em {}", Local::now().format("%d/%m/%Y %H:%M")),
+        7.0,
+        Mm(MARGIN),
+        Mm(MARGIN - 7.0),
+        &regular,
+    );
+
+    let file = File::create(path)
+        .map_err(|e| format!("Failed to create {}: {}", path, e))?;
+    doc.save(&mut BufWriter::new(file))
+        .map_err(|e| format!("Failed to write PDF: {}", e))
+}
+
+/// Exporta o histórico do período no formato escolhido. O caminho vem do
+/// diálogo de salvar arquivo do frontend.
+#[tauri::command]
+pub async fn export_timesheet(
+    app: AppHandle,
+    path: String,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+    format: ExportFormat,
+    employee_name: Option<String>,
+) -> Result<(), String> {
+    if end_date < start_date {
+        return Err("A data final deve ser posterior à data inicial".to_string());
+    }
+
+    let history = history::load_history(&app)?;
+    let schedule = schedule::load_schedule(&app)?;
+    let timesheet = timesheet::build_timesheet(&history, &schedule, start_date, end_date);
+
+    match format {
+        ExportFormat::Csv => std::fs::write(&path, timesheet_csv(&timesheet))
+            .map_err(|e| format!("Failed to write {}: {}", path, e))?,
+        ExportFormat::Json => std::fs::write(&path, timesheet_json(&timesheet)?)
+            .map_err(|e| format!("Failed to write {}: {}", path, e))?,
+        ExportFormat::Pdf => write_timesheet_pdf(&timesheet, employee_name.as_deref().unwrap_or_default(), &path)?,
+    }
+
+    println!("Timesheet exported to {}", path);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveTime;
+    use crate::history::{PunchHistory, PunchSource};
+    use crate::schedule::WorkSchedule;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 3, day).unwrap()
+    }
+
+    fn timesheet(days: &[(u32, &[&str])], start: u32, end: u32) -> Timesheet {
+        let mut history = PunchHistory::default();
+        for (day, times) in days {
+            let times: Vec<NaiveTime> = times
+                .iter()
+                .map(|time| NaiveTime::parse_from_str(time, "%H:%M").unwrap())
+                .collect();
+            history.set_day(date(*day), &times, PunchSource::Manual);
+        }
+        timesheet::build_timesheet(&history, &WorkSchedule::default(), date(start), date(end))
+    }
+
+    #[test]
+    fn csv_has_one_row_per_day_and_a_total() {
+        let timesheet = timesheet(&[
+            (10, &["08:00", "12:00", "13:00", "17:30"]),
+            (11, &["08:00", "12:00", "13:00", "16:00"]),
+        ], 10, 12);
+
+        let csv = timesheet_csv(&timesheet);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines, vec![
+            "data;dia;marcacoes;trabalhado;meta;extra;adicional;saldo;banco_de_horas",
+            "10/03/2025;Seg;08:00 12:00 13:00 17:30;8h30;8h00;0h30;50%;0h30;0h30",
+            "11/03/2025;Ter;08:00 12:00 13:00 16:00;7h00;8h00;0h00;50%;-1h00;-0h30",
+            "12/03/2025;Qua;;0h00;8h00;0h00;50%;-8h00;-8h30",
+            "total;;;15h30;24h00;0h30;;-8h30;-8h30",
+        ]);
+    }
+
+    #[test]
+    fn csv_marks_sunday_overtime_at_100_percent() {
+        let timesheet = timesheet(&[(16, &["09:00", "11:00"])], 16, 16);
+
+        let csv = timesheet_csv(&timesheet);
+        assert_eq!(csv.lines().nth(1), Some("16/03/2025;Dom;09:00 11:00;2h00;0h00;2h00;100%;2h00;2h00"));
+    }
+
+    #[test]
+    fn json_keeps_minutes_and_totals() {
+        let timesheet = timesheet(&[
+            (14, &["08:00", "12:00", "13:00", "18:00"]),
+            (16, &["09:00", "11:00"]),
+        ], 14, 16);
+
+        let json: serde_json::Value = serde_json::from_str(&timesheet_json(&timesheet).unwrap()).unwrap();
+        let days = json["days"].as_array().unwrap();
+        assert_eq!(days.len(), 3);
+        assert_eq!(days[0]["date"], "2025-03-14");
+        assert_eq!(days[0]["punches"], serde_json::json!(["08:00", "12:00", "13:00", "18:00"]));
+        assert_eq!(days[0]["worked_minutes"], 540);
+        assert_eq!(days[0]["overtime_minutes"], 60);
+        assert_eq!(days[1]["punches"], serde_json::json!([]));
+        assert_eq!(days[2]["overtime_rate"], 100);
+        assert_eq!(json["total_overtime_minutes"], 180);
+        assert_eq!(json["total_overtime_100_minutes"], 120);
+        assert_eq!(json["balance_minutes"], 180);
+    }
+}
@@ -0,0 +1,129 @@
+use chrono::{NaiveDate, NaiveTime};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+#[cfg(feature = "app")]
+use tauri::AppHandle;
+#[cfg(feature = "app")]
+use tauri_plugin_store::StoreExt;
+
+use crate::storage::KeyValueStore;
+
+const HISTORY_KEY: &str = "punch_history";
+
+/// Origem de uma marcação salva no histórico local.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PunchSource {
+    Manual,
+    Provider,
+    Import,
+    Afd,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PunchKind {
+    Entrada,
+    Saida,
+}
+
+impl PunchKind {
+    /// Marcações alternam entre entrada e saída, começando pela entrada.
+    pub fn for_position(index: usize) -> Self {
+        if index.is_multiple_of(2) { PunchKind::Entrada } else { PunchKind::Saida }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HistoryPunch {
+    pub time: NaiveTime,
+    pub source: PunchSource,
+}
+
+/// Histórico local de marcações, indexado por dia.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PunchHistory {
+    days: BTreeMap<NaiveDate, Vec<HistoryPunch>>,
+}
+
+impl PunchHistory {
+    pub fn punches(&self, date: NaiveDate) -> &[HistoryPunch] {
+        self.days.get(&date).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    pub fn times(&self, date: NaiveDate) -> Vec<NaiveTime> {
+        self.punches(date).iter().map(|punch| punch.time).collect()
+    }
+
+    /// Substitui as marcações do dia; um dia sem marcações é removido do histórico.
+    pub fn set_day(&mut self, date: NaiveDate, times: &[NaiveTime], source: PunchSource) {
+        let mut punches: Vec<HistoryPunch> = times
+            .iter()
+            .map(|&time| HistoryPunch { time, source })
+            .collect();
+        punches.sort_by_key(|punch| punch.time);
+        punches.dedup_by_key(|punch| punch.time);
+
+        if punches.is_empty() {
+            self.days.remove(&date);
+        } else {
+            self.days.insert(date, punches);
+        }
+    }
+
+    /// Dias com marcações entre `start` e `end` (inclusive).
+    pub fn range(&self, start: NaiveDate, end: NaiveDate) -> impl Iterator<Item = (NaiveDate, &[HistoryPunch])> {
+        self.days
+            .range(start..=end)
+            .map(|(date, punches)| (*date, punches.as_slice()))
+    }
+}
+
+/// Minutos trabalhados somando cada par entrada/saída. Uma entrada sem saída
+/// correspondente é ignorada.
+pub fn worked_minutes(times: &[NaiveTime]) -> i64 {
+    times
+        .chunks_exact(2)
+        .map(|pair| (pair[1] - pair[0]).num_minutes())
+        .sum()
+}
+
+#[cfg(feature = "app")]
+pub fn load_history(app: &AppHandle) -> Result<PunchHistory, String> {
+    let store = app.store("noponto.dat")
+        .map_err(|e| format!("Failed to get store: {}", e))?;
+
+    read_history(store.as_ref())
+}
+
+#[cfg(feature = "app")]
+pub fn save_history(app: &AppHandle, history: &PunchHistory) -> Result<(), String> {
+    let store = app.store("noponto.dat")
+        .map_err(|e| format!("Failed to get store: {}", e))?;
+
+    write_history(store.as_ref(), history)
+}
+
+pub fn read_history(store: &dyn KeyValueStore) -> Result<PunchHistory, String> {
+    match store.get(HISTORY_KEY) {
+        Some(value) => serde_json::from_value(value)
+            .map_err(|e| format!("Failed to parse punch history: {}", e)),
+        None => Ok(PunchHistory::default()),
+    }
+}
+
+pub fn write_history(store: &dyn KeyValueStore, history: &PunchHistory) -> Result<(), String> {
+    let value = serde_json::to_value(history)
+        .map_err(|e| format!("Failed to serialize punch history: {}", e))?;
+    store.set(HISTORY_KEY, value);
+
+    store.save()
+}
+
+#[cfg(feature = "app")]
+/// Grava as marcações de um dia, preservando o restante do histórico.
+pub fn record_day(app: &AppHandle, date: NaiveDate, times: &[NaiveTime], source: PunchSource) -> Result<(), String> {
+    let mut history = load_history(app)?;
+    history.set_day(date, times, source);
+    save_history(app, &history)
+}
@@ -0,0 +1,329 @@
+//! Calendário de feriados: nacionais (fixos e móveis, calculados a partir da
+//! Páscoa), estaduais embutidos por UF e feriados municipais ou folgas da
+//! empresa configurados pelo usuário.
+
+use chrono::{Datelike, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "app")]
+use tauri::AppHandle;
+#[cfg(feature = "app")]
+use tauri_plugin_store::StoreExt;
+
+use crate::storage::KeyValueStore;
+
+const CONFIG_KEY: &str = "holiday_config";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HolidayScope {
+    National,
+    /// Ponto facultativo (Carnaval, Corpus Christi): só é folga se configurado.
+    Optional,
+    State,
+    Municipal,
+    Company,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Holiday {
+    pub date: NaiveDate,
+    pub name: String,
+    pub scope: HolidayScope,
+}
+
+/// Feriado configurado pelo usuário. `date` aceita `MM-DD` (todo ano) ou
+/// `AAAA-MM-DD` (apenas naquela data).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CustomHoliday {
+    pub date: String,
+    pub name: String,
+    pub scope: HolidayScope,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HolidayConfig {
+    /// Sigla da UF para os feriados estaduais embutidos (ex.: "SP").
+    #[serde(default)]
+    pub state: Option<String>,
+    /// Se os pontos facultativos contam como folga.
+    #[serde(default)]
+    pub observe_optional: bool,
+    /// Feriados municipais e folgas da empresa.
+    #[serde(default)]
+    pub custom: Vec<CustomHoliday>,
+}
+
+const NATIONAL_FIXED: &[(u32, u32, &str)] = &[
+    (1, 1, "Confraternização Universal"),
+    (4, 21, "Tiradentes"),
+    (5, 1, "Dia do Trabalho"),
+    (9, 7, "Independência do Brasil"),
+    (10, 12, "Nossa Senhora Aparecida"),
+    (11, 2, "Finados"),
+    (11, 15, "Proclamação da República"),
+    (12, 25, "Natal"),
+];
+
+/// Feriado nacional desde 2024 (Lei 14.759/2023); antes disso, só onde havia
+/// lei estadual ou municipal.
+const CONSCIENCIA_NEGRA: (u32, u32, &str) = (11, 20, "Dia Nacional de Zumbi e da Consciência Negra");
+const CONSCIENCIA_NEGRA_SINCE: i32 = 2024;
+
+const STATE_FIXED: &[(&str, u32, u32, &str)] = &[
+    ("AC", 1, 23, "Dia do Evangélico"),
+    ("AC", 6, 15, "Aniversário do Acre"),
+    ("AC", 9, 5, "Dia da Amazônia"),
+    ("AC", 11, 17, "Assinatura do Tratado de Petrópolis"),
+    ("AL", 6, 24, "São João"),
+    ("AL", 6, 29, "São Pedro"),
+    ("AL", 9, 16, "Emancipação Política de Alagoas"),
+    ("AL", 11, 20, "Dia da Consciência Negra"),
+    ("AM", 9, 5, "Elevação do Amazonas à categoria de província"),
+    ("AM", 11, 20, "Dia da Consciência Negra"),
+    ("AP", 3, 19, "Dia de São José"),
+    ("AP", 9, 13, "Criação do Território Federal do Amapá"),
+    ("AP", 11, 20, "Dia da Consciência Negra"),
+    ("BA", 7, 2, "Independência da Bahia"),
+    ("CE", 3, 19, "Dia de São José"),
+    ("CE", 3, 25, "Data Magna do Ceará"),
+    ("DF", 11, 30, "Dia do Evangélico"),
+    ("MA", 7, 28, "Adesão do Maranhão à Independência"),
+    ("MS", 10, 11, "Criação do Estado de Mato Grosso do Sul"),
+    ("MT", 11, 20, "Dia da Consciência Negra"),
+    ("PA", 8, 15, "Adesão do Pará à Independência"),
+    ("PB", 8, 5, "Fundação do Estado da Paraíba"),
+    ("PE", 3, 6, "Revolução Pernambucana"),
+    ("PI", 3, 13, "Dia da Batalha do Jenipapo"),
+    ("PI", 10, 19, "Dia do Piauí"),
+    ("PR", 12, 19, "Emancipação Política do Paraná"),
+    ("RJ", 4, 23, "Dia de São Jorge"),
+    ("RJ", 11, 20, "Dia da Consciência Negra"),
+    ("RN", 10, 3, "Mártires de Cunhaú e Uruaçu"),
+    ("RO", 1, 4, "Criação do Estado de Rondônia"),
+    ("RO", 6, 18, "Dia do Evangélico"),
+    ("RR", 10, 5, "Criação do Estado de Roraima"),
+    ("RS", 9, 20, "Revolução Farroupilha"),
+    ("SC", 8, 11, "Data Magna de Santa Catarina"),
+    ("SE", 7, 8, "Emancipação Política de Sergipe"),
+    ("SP", 7, 9, "Revolução Constitucionalista"),
+    ("TO", 3, 18, "Autonomia do Tocantins"),
+    ("TO", 9, 8, "Nossa Senhora da Natividade"),
+    ("TO", 10, 5, "Criação do Estado do Tocantins"),
+];
+
+fn national_fixed(year: i32, month: u32, day: u32) -> bool {
+    NATIONAL_FIXED.iter().any(|&(m, d, _)| (m, d) == (month, day))
+        || (year >= CONSCIENCIA_NEGRA_SINCE && (CONSCIENCIA_NEGRA.0, CONSCIENCIA_NEGRA.1) == (month, day))
+}
+
+/// Domingo de Páscoa pelo algoritmo de Meeus/Jones/Butcher.
+pub fn easter_sunday(year: i32) -> NaiveDate {
+    let a = year % 19;
+    let b = year / 100;
+    let c = year % 100;
+    let d = b / 4;
+    let e = b % 4;
+    let f = (b + 8) / 25;
+    let g = (b - f + 1) / 3;
+    let h = (19 * a + b - d - g + 15) % 30;
+    let i = c / 4;
+    let k = c % 4;
+    let l = (32 + 2 * e + 2 * i - h - k) % 7;
+    let m = (a + 11 * h + 22 * l) / 451;
+    let month = (h + l - 7 * m + 114) / 31;
+    let day = (h + l - 7 * m + 114) % 31 + 1;
+
+    NaiveDate::from_ymd_opt(year, month as u32, day as u32).expect("valid Easter date")
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct HolidayCalendar {
+    config: HolidayConfig,
+}
+
+impl HolidayCalendar {
+    pub fn new(config: HolidayConfig) -> Self {
+        HolidayCalendar { config }
+    }
+
+    /// Todos os feriados do ano, inclusive pontos facultativos, em ordem de data.
+    pub fn holidays_in(&self, year: i32) -> Vec<Holiday> {
+        let mut holidays = Vec::new();
+        let mut push = |date: Option<NaiveDate>, name: &str, scope: HolidayScope| {
+            if let Some(date) = date {
+                holidays.push(Holiday { date, name: name.to_string(), scope });
+            }
+        };
+
+        for &(month, day, name) in NATIONAL_FIXED {
+            push(NaiveDate::from_ymd_opt(year, month, day), name, HolidayScope::National);
+        }
+        if year >= CONSCIENCIA_NEGRA_SINCE {
+            let (month, day, name) = CONSCIENCIA_NEGRA;
+            push(NaiveDate::from_ymd_opt(year, month, day), name, HolidayScope::National);
+        }
+
+        let easter = easter_sunday(year);
+        let from_easter = |days: i64| Some(easter + chrono::Duration::days(days));
+        push(from_easter(-48), "Carnaval", HolidayScope::Optional);
+        push(from_easter(-47), "Carnaval", HolidayScope::Optional);
+        push(from_easter(-2), "Sexta-feira Santa", HolidayScope::National);
+        push(from_easter(60), "Corpus Christi", HolidayScope::Optional);
+
+        if let Some(state) = &self.config.state {
+            for &(uf, month, day, name) in STATE_FIXED {
+                // Feriados estaduais que coincidem com um nacional aparecem uma vez só
+                if uf.eq_ignore_ascii_case(state) && !national_fixed(year, month, day) {
+                    push(NaiveDate::from_ymd_opt(year, month, day), name, HolidayScope::State);
+                }
+            }
+        }
+
+        for custom in &self.config.custom {
+            let date = NaiveDate::parse_from_str(&custom.date, "%Y-%m-%d")
+                .ok()
+                .or_else(|| NaiveDate::parse_from_str(&format!("{}-{}", year, custom.date), "%Y-%m-%d").ok())
+                .filter(|date| date.year() == year);
+            push(date, &custom.name, custom.scope);
+        }
+
+        holidays.sort_by_key(|holiday| holiday.date);
+        holidays
+    }
+
+    /// Feriado que dá folga na data. Pontos facultativos só contam quando
+    /// `observe_optional` está ativo.
+    pub fn holiday_on(&self, date: NaiveDate) -> Option<Holiday> {
+        self.holidays_in(date.year())
+            .into_iter()
+            .filter(|holiday| holiday.scope != HolidayScope::Optional || self.config.observe_optional)
+            .find(|holiday| holiday.date == date)
+    }
+
+    /// Horas extras em domingos e feriados são pagas com adicional de 100%;
+    /// nos demais dias, 50%.
+    pub fn overtime_rate(&self, date: NaiveDate) -> u32 {
+        if date.weekday() == Weekday::Sun || self.holiday_on(date).is_some() {
+            100
+        } else {
+            50
+        }
+    }
+}
+
+#[cfg(feature = "app")]
+pub fn load_holiday_config(app: &AppHandle) -> Result<HolidayConfig, String> {
+    let store = app.store("noponto.dat")
+        .map_err(|e| format!("Failed to get store: {}", e))?;
+
+    read_holiday_config(store.as_ref())
+}
+
+pub fn read_holiday_config(store: &dyn KeyValueStore) -> Result<HolidayConfig, String> {
+    match store.get(CONFIG_KEY) {
+        Some(value) => serde_json::from_value(value)
+            .map_err(|e| format!("Failed to parse holiday config: {}", e)),
+        None => Ok(HolidayConfig::default()),
+    }
+}
+
+#[cfg(feature = "app")]
+#[tauri::command]
+pub async fn get_holiday_config(app: AppHandle) -> Result<HolidayConfig, String> {
+    load_holiday_config(&app)
+}
+
+#[cfg(feature = "app")]
+#[tauri::command]
+pub async fn save_holiday_config(app: AppHandle, config: HolidayConfig) -> Result<(), String> {
+    for custom in &config.custom {
+        let valid = NaiveDate::parse_from_str(&custom.date, "%Y-%m-%d").is_ok()
+            || NaiveDate::parse_from_str(&format!("2024-{}", custom.date), "%Y-%m-%d").is_ok();
+        if !valid {
+            return Err(format!("Data inválida para o feriado {}: {}", custom.name, custom.date));
+        }
+    }
+
+    let store = app.store("noponto.dat")
+        .map_err(|e| format!("Failed to get store: {}", e))?;
+
+    let value = serde_json::to_value(&config)
+        .map_err(|e| format!("Failed to serialize holiday config: {}", e))?;
+    store.set(CONFIG_KEY, value);
+
+    store.save()
+        .map_err(|e| format!("Failed to save store: {}", e))
+}
+
+#[cfg(feature = "app")]
+#[tauri::command]
+pub async fn get_holidays(app: AppHandle, year: i32) -> Result<Vec<Holiday>, String> {
+    let calendar = HolidayCalendar::new(load_holiday_config(&app)?);
+    Ok(calendar.holidays_in(year))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn calendar(state: Option<&str>) -> HolidayCalendar {
+        HolidayCalendar::new(HolidayConfig {
+            state: state.map(str::to_string),
+            ..HolidayConfig::default()
+        })
+    }
+
+    #[test]
+    fn computes_easter_for_known_years() {
+        assert_eq!(easter_sunday(2000), date(2000, 4, 23));
+        assert_eq!(easter_sunday(2019), date(2019, 4, 21));
+        assert_eq!(easter_sunday(2024), date(2024, 3, 31));
+        assert_eq!(easter_sunday(2025), date(2025, 4, 20));
+        assert_eq!(easter_sunday(2026), date(2026, 4, 5));
+        assert_eq!(easter_sunday(2038), date(2038, 4, 25));
+    }
+
+    #[test]
+    fn computes_movable_holidays() {
+        let holidays = calendar(None).holidays_in(2026);
+        let on = |name: &str| -> Vec<NaiveDate> {
+            holidays.iter().filter(|holiday| holiday.name == name).map(|holiday| holiday.date).collect()
+        };
+
+        assert_eq!(on("Carnaval"), vec![date(2026, 2, 16), date(2026, 2, 17)]);
+        assert_eq!(on("Sexta-feira Santa"), vec![date(2026, 4, 3)]);
+        assert_eq!(on("Corpus Christi"), vec![date(2026, 6, 4)]);
+    }
+
+    #[test]
+    fn optional_holidays_only_count_when_observed() {
+        assert!(calendar(None).holiday_on(date(2025, 3, 4)).is_none());
+
+        let observed = HolidayCalendar::new(HolidayConfig { observe_optional: true, ..HolidayConfig::default() });
+        assert_eq!(observed.holiday_on(date(2025, 3, 4)).map(|holiday| holiday.scope), Some(HolidayScope::Optional));
+    }
+
+    #[test]
+    fn consciencia_negra_is_national_since_2024() {
+        assert!(calendar(None).holiday_on(date(2023, 11, 20)).is_none());
+        assert_eq!(calendar(Some("RJ")).holiday_on(date(2023, 11, 20)).map(|holiday| holiday.scope), Some(HolidayScope::State));
+
+        let in_2024 = calendar(Some("RJ")).holidays_in(2024);
+        let on_date: Vec<&Holiday> = in_2024.iter().filter(|holiday| holiday.date == date(2024, 11, 20)).collect();
+        assert_eq!(on_date.len(), 1);
+        assert_eq!(on_date[0].scope, HolidayScope::National);
+    }
+
+    #[test]
+    fn includes_state_holidays() {
+        for (state, month, day) in [("PE", 3, 6), ("AL", 9, 16), ("PB", 8, 5), ("RN", 10, 3), ("SC", 8, 11)] {
+            let holiday = calendar(Some(state)).holiday_on(date(2026, month, day));
+            assert_eq!(holiday.map(|holiday| holiday.scope), Some(HolidayScope::State), "{}", state);
+        }
+        assert!(calendar(Some("SP")).holiday_on(date(2026, 3, 6)).is_none());
+    }
+}
@@ -0,0 +1,293 @@
+//! API HTTP local opcional para scripts e plugins de editor. Escuta apenas em
+//! 127.0.0.1 e exige o token configurado (`Authorization: Bearer <token>`; só
+//! a rota de Server-Sent Events aceita também `?token=`, para o `EventSource`).
+
+use axum::{
+    extract::{Query, Request, State as AxumState},
+    http::{header, StatusCode},
+    middleware::{self, Next},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
+    routing::{get, post},
+    Json, Router,
+};
+use chrono::{Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_store::StoreExt;
+use tokio::sync::watch;
+use tokio::time::{timeout, Duration};
+use tokio_stream::{
+    wrappers::{BroadcastStream, WatchStream},
+    Stream, StreamExt,
+};
+
+use crate::events::{self, WorkEvent};
+use crate::{control, history, schedule, timesheet, SharedState, TimeData};
+
+const SETTINGS_KEY: &str = "http_api";
+const DEFAULT_PORT: u16 = 4848;
+const EVENTS_PATH: &str = "/events";
+/// Tempo máximo para as conexões abertas terminarem ao parar o servidor.
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HttpApiSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_port")]
+    pub port: u16,
+    /// Gerado na primeira ativação; enviado pelos clientes em cada requisição.
+    #[serde(default)]
+    pub token: String,
+}
+
+fn default_port() -> u16 {
+    DEFAULT_PORT
+}
+
+impl Default for HttpApiSettings {
+    fn default() -> Self {
+        HttpApiSettings { enabled: false, port: DEFAULT_PORT, token: String::new() }
+    }
+}
+
+/// Estado do servidor gerenciado pelo Tauri.
+#[derive(Default)]
+pub struct HttpApi {
+    server: Mutex<Option<RunningServer>>,
+}
+
+struct RunningServer {
+    task: tokio::task::JoinHandle<()>,
+    /// Sinaliza o encerramento ao servidor e aos streams SSE abertos.
+    shutdown: watch::Sender<bool>,
+}
+
+#[derive(Clone)]
+struct ApiState {
+    app: AppHandle,
+    token: String,
+    shutdown: watch::Receiver<bool>,
+}
+
+type ApiResult = Result<Json<Value>, (StatusCode, String)>;
+
+fn internal_error(e: String) -> (StatusCode, String) {
+    (StatusCode::INTERNAL_SERVER_ERROR, e)
+}
+
+async fn require_token(
+    AxumState(state): AxumState<ApiState>,
+    Query(query): Query<HashMap<String, String>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let bearer = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    let query_token = match request.uri().path() {
+        EVENTS_PATH => query.get("token").map(String::as_str),
+        _ => None,
+    };
+    let token = bearer.or(query_token);
+
+    if !token.is_some_and(|token| tokens_match(token, &state.token)) {
+        return (StatusCode::UNAUTHORIZED, "Token inválido").into_response();
+    }
+    next.run(request).await
+}
+
+/// Compara em tempo constante, sem revelar pelo tempo de resposta quantos
+/// caracteres do token estão certos.
+fn tokens_match(received: &str, expected: &str) -> bool {
+    received.len() == expected.len()
+        && received.bytes().zip(expected.bytes()).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+fn current_status(app: &AppHandle) -> Value {
+    let status = app.state::<SharedState>().lock().unwrap().clone();
+    serde_json::to_value(status).unwrap_or(Value::Null)
+}
+
+async fn get_status(AxumState(state): AxumState<ApiState>) -> ApiResult {
+    Ok(Json(current_status(&state.app)))
+}
+
+async fn start_monitoring(AxumState(state): AxumState<ApiState>, Json(times): Json<TimeData>) -> ApiResult {
+    let shared = state.app.state::<SharedState>().inner().clone();
+    crate::save_time_data(&state.app, &times).map_err(internal_error)?;
+    crate::begin_work_monitoring(&state.app, &shared, &times.inicio1, &times.fim1, &times.inicio2)
+        .await
+        .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+    Ok(Json(current_status(&state.app)))
+}
+
+async fn stop_monitoring(AxumState(state): AxumState<ApiState>) -> ApiResult {
+    let shared = state.app.state::<SharedState>().inner().clone();
+    crate::end_work_monitoring(&state.app, &shared);
+    Ok(Json(Value::Null))
+}
+
+async fn sync(AxumState(state): AxumState<ApiState>) -> ApiResult {
+    let message = crate::sync_and_start_monitoring(&state.app).await.map_err(internal_error)?;
+    Ok(Json(serde_json::json!({ "message": message, "status": current_status(&state.app) })))
+}
+
+#[derive(Deserialize)]
+struct HistoryQuery {
+    start: Option<NaiveDate>,
+    end: Option<NaiveDate>,
+}
+
+/// Espelho do período; sem parâmetros, os últimos 7 dias.
+async fn get_history(AxumState(state): AxumState<ApiState>, Query(query): Query<HistoryQuery>) -> ApiResult {
+    let end_date = query.end.unwrap_or_else(|| Local::now().date_naive());
+    let start_date = query.start.unwrap_or(end_date - chrono::Duration::days(6));
+    if end_date < start_date {
+        return Err((StatusCode::BAD_REQUEST, "A data final deve ser posterior à data inicial".to_string()));
+    }
+
+    let history = history::load_history(&state.app).map_err(internal_error)?;
+    let schedule = schedule::load_schedule(&state.app).map_err(internal_error)?;
+    let timesheet = timesheet::build_timesheet(&history, &schedule, start_date, end_date);
+    serde_json::to_value(timesheet).map(Json).map_err(|e| internal_error(e.to_string()))
+}
+
+/// Nome e dados do evento SSE correspondente, para os eventos expostos.
+fn sse_event(event: WorkEvent) -> Option<(&'static str, Value)> {
+    match event {
+        WorkEvent::AlmostComplete { remaining_minutes, .. } => Some(("work_almost_complete", serde_json::json!(remaining_minutes))),
+        WorkEvent::Complete { .. } => Some(("work_complete", Value::Null)),
+        _ => None,
+    }
+}
+
+async fn events(AxumState(state): AxumState<ApiState>) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let receiver = events::subscribe(&state.app);
+    // O stream termina quando o servidor é parado ou reiniciado com outro token.
+    let closed = WatchStream::from_changes(state.shutdown.clone()).map(|_| None);
+    let stream = BroadcastStream::new(receiver)
+        .filter_map(|event| {
+            let (name, data) = sse_event(event.ok()?)?;
+            Some(Ok(Event::default().event(name).data(data.to_string())))
+        })
+        .map(Some)
+        .merge(closed)
+        .map_while(|event| event);
+
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+fn router(app: AppHandle, token: String, shutdown: watch::Receiver<bool>) -> Router {
+    let state = ApiState { app, token, shutdown };
+
+    Router::new()
+        .route("/status", get(get_status))
+        .route("/monitoring/start", post(start_monitoring))
+        .route("/monitoring/stop", post(stop_monitoring))
+        .route("/sync", post(sync))
+        .route("/history", get(get_history))
+        .route(EVENTS_PATH, get(events))
+        .layer(middleware::from_fn_with_state(state.clone(), require_token))
+        .with_state(state)
+}
+
+fn load_settings(app: &AppHandle) -> Result<HttpApiSettings, String> {
+    let store = app.store("noponto.dat")
+        .map_err(|e| format!("Failed to get store: {}", e))?;
+
+    match store.get(SETTINGS_KEY) {
+        Some(value) => serde_json::from_value(value)
+            .map_err(|e| format!("Failed to parse HTTP API settings: {}", e)),
+        None => Ok(HttpApiSettings::default()),
+    }
+}
+
+/// Encerra o servidor em execução, abortando-o se não parar a tempo.
+async fn stop_server(api: &HttpApi) {
+    let running = api.server.lock().unwrap().take();
+    if let Some(mut running) = running {
+        let _ = running.shutdown.send(true);
+        if timeout(SHUTDOWN_TIMEOUT, &mut running.task).await.is_err() {
+            running.task.abort();
+        }
+    }
+}
+
+/// Para o servidor ao encerrar o app.
+pub async fn stop(app: &AppHandle) {
+    stop_server(&app.state::<HttpApi>()).await;
+}
+
+/// Inicia, reinicia ou para o servidor conforme as configurações salvas.
+pub async fn apply_settings(app: &AppHandle) -> Result<(), String> {
+    let settings = load_settings(app)?;
+    let api = app.state::<HttpApi>();
+
+    stop_server(&api).await;
+    if !settings.enabled {
+        return Ok(());
+    }
+
+    let listener = tokio::net::TcpListener::bind(("127.0.0.1", settings.port))
+        .await
+        .map_err(|e| format!("Failed to bind HTTP API on port {}: {}", settings.port, e))?;
+    println!("HTTP API listening on http://127.0.0.1:{}", settings.port);
+
+    let (shutdown, mut signal) = watch::channel(false);
+    let router = router(app.clone(), settings.token, signal.clone());
+    let task = tokio::spawn(async move {
+        let stopped = async move {
+            let _ = signal.wait_for(|stop| *stop).await;
+        };
+        if let Err(e) = axum::serve(listener, router).with_graceful_shutdown(stopped).await {
+            println!("HTTP API stopped: {}", e);
+        }
+    });
+    *api.server.lock().unwrap() = Some(RunningServer { task, shutdown });
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_http_api_settings(app: AppHandle) -> Result<HttpApiSettings, String> {
+    load_settings(&app)
+}
+
+/// Salva as configurações e aplica na hora. Um token novo é gerado na primeira
+/// ativação ou quando `regenerate_token` é verdadeiro.
+#[tauri::command]
+pub async fn save_http_api_settings(
+    app: AppHandle,
+    enabled: bool,
+    port: Option<u16>,
+    regenerate_token: Option<bool>,
+) -> Result<HttpApiSettings, String> {
+    let mut settings = load_settings(&app)?;
+    settings.enabled = enabled;
+    settings.port = port.unwrap_or(settings.port);
+
+    if settings.token.is_empty() || regenerate_token.unwrap_or(false) {
+        settings.token = control::random_token();
+    }
+
+    let store = app.store("noponto.dat")
+        .map_err(|e| format!("Failed to get store: {}", e))?;
+    let value = serde_json::to_value(&settings)
+        .map_err(|e| format!("Failed to serialize HTTP API settings: {}", e))?;
+    store.set(SETTINGS_KEY, value);
+    store.save()
+        .map_err(|e| format!("Failed to save store: {}", e))?;
+
+    apply_settings(&app).await?;
+    Ok(settings)
+}
@@ -0,0 +1,509 @@
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+#[cfg(feature = "app")]
+use tauri::{AppHandle, Manager, State};
+#[cfg(feature = "app")]
+use tauri_plugin_store::StoreExt;
+
+use crate::history::{self, PunchHistory};
+use crate::storage::KeyValueStore;
+#[cfg(feature = "app")]
+use crate::SharedState;
+
+const FEED_PATH_KEY: &str = "calendar_feed_path";
+/// Dias anteriores incluídos no feed de calendário.
+const FEED_DAYS_BACK: i64 = 30;
+
+struct CalendarEvent {
+    uid: String,
+    summary: String,
+    start: DateTime<Local>,
+    end: DateTime<Local>,
+    transparent: bool,
+}
+
+fn escape_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+fn utc_stamp(datetime: DateTime<Local>) -> String {
+    datetime.with_timezone(&Utc).format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// Quebra linhas com mais de 75 octetos, como exige a RFC 5545.
+fn fold_line(line: &str) -> String {
+    let mut folded = String::new();
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            width = 1;
+        }
+        folded.push(c);
+        width += c.len_utf8();
+    }
+    folded
+}
+
+fn local(datetime: NaiveDateTime) -> Option<DateTime<Local>> {
+    Local.from_local_datetime(&datetime).earliest()
+}
+
+/// Períodos trabalhados do histórico e, para hoje, o período previsto até a
+/// saída estimada pelo monitoramento.
+fn work_events(
+    history: &PunchHistory,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+    expected_end: Option<DateTime<Local>>,
+) -> Vec<CalendarEvent> {
+    let mut events = Vec::new();
+    let today = Local::now().date_naive();
+
+    for (date, punches) in history.range(start_date, end_date) {
+        for (index, pair) in punches.chunks(2).enumerate() {
+            let Some(start) = local(date.and_time(pair[0].time)) else { continue };
+            let uid = format!("work-{}-{}@noponto", date.format("%Y%m%d"), index + 1);
+
+            match (pair.get(1), expected_end) {
+                (Some(exit), _) => {
+                    if let Some(end) = local(date.and_time(exit.time)) {
+                        events.push(CalendarEvent { uid, summary: "Trabalho".to_string(), start, end, transparent: false });
+                    }
+                }
+                (None, Some(end)) if date == today && end > start => {
+                    events.push(CalendarEvent { uid, summary: "Trabalho (previsto)".to_string(), start, end, transparent: false });
+                }
+                _ => {}
+            }
+        }
+    }
+
+    if let Some(end) = expected_end.filter(|end| end.date_naive() >= start_date && end.date_naive() <= end_date) {
+        events.push(CalendarEvent {
+            // UID fixo por dia para que o calendário substitua o evento quando a previsão mudar
+            uid: format!("expected-end-{}@noponto", end.format("%Y%m%d")),
+            summary: format!("Saída prevista {}", end.format("%H:%M")),
+            start: end,
+            end: end + chrono::Duration::minutes(5),
+            transparent: true,
+        });
+    }
+
+    events
+}
+
+fn write_calendar(events: &[CalendarEvent]) -> String {
+    let now = Local::now();
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//NoPonto//Controle de Ponto//PT-BR".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        "X-WR-CALNAME:NoPonto".to_string(),
+    ];
+
+    for event in events {
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!("UID:{}", event.uid));
+        lines.push(format!("DTSTAMP:{}", utc_stamp(now)));
+        lines.push(format!("LAST-MODIFIED:{}", utc_stamp(now)));
+        lines.push(format!("SEQUENCE:{}", now.timestamp() / 60));
+        lines.push(format!("DTSTART:{}", utc_stamp(event.start)));
+        lines.push(format!("DTEND:{}", utc_stamp(event.end)));
+        lines.push(format!("SUMMARY:{}", escape_text(&event.summary)));
+        if event.transparent {
+            lines.push("TRANSP:TRANSPARENT".to_string());
+        }
+        lines.push("END:VEVENT".to_string());
+    }
+
+    lines.push("END:VCALENDAR".to_string());
+    lines.iter().map(|line| fold_line(line) + "\r\n").collect()
+}
+
+/// Evento lido de um arquivo iCalendar. Horários já convertidos para o fuso local.
+#[derive(Debug, Clone)]
+pub struct ParsedEvent {
+    pub uid: String,
+    pub summary: String,
+    pub categories: Vec<String>,
+    pub start: NaiveDateTime,
+    pub end: NaiveDateTime,
+    pub all_day: bool,
+}
+
+fn unescape_text(text: &str) -> String {
+    let mut unescaped = String::new();
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') | Some('N') => unescaped.push('\n'),
+            Some(other) => unescaped.push(other),
+            None => {}
+        }
+    }
+    unescaped
+}
+
+/// Interpreta `DTSTART`/`DTEND` nas formas data (`VALUE=DATE`), UTC (`Z`) ou
+/// hora local. Horários com `TZID` são tratados como hora local (o fuso é
+/// registrado no log por `parse_calendar`).
+fn parse_ics_datetime(value: &str) -> Option<(NaiveDateTime, bool)> {
+    if value.len() == 8 {
+        let date = NaiveDate::parse_from_str(value, "%Y%m%d").ok()?;
+        return Some((date.and_time(NaiveTime::MIN), true));
+    }
+
+    if let Some(utc) = value.strip_suffix('Z') {
+        let naive = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok()?;
+        return Some((Utc.from_utc_datetime(&naive).with_timezone(&Local).naive_local(), false));
+    }
+
+    NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok().map(|naive| (naive, false))
+}
+
+/// Durações simples como `PT1H30M` ou `P1D`.
+fn parse_ics_duration(value: &str) -> Option<chrono::Duration> {
+    let value = value.strip_prefix('P')?;
+    let mut total = chrono::Duration::zero();
+    let mut number = String::new();
+    for c in value.chars() {
+        match c {
+            '0'..='9' => number.push(c),
+            'T' => {}
+            unit => {
+                let amount: i64 = number.parse().ok()?;
+                number.clear();
+                total += match unit {
+                    'W' => chrono::Duration::weeks(amount),
+                    'D' => chrono::Duration::days(amount),
+                    'H' => chrono::Duration::hours(amount),
+                    'M' => chrono::Duration::minutes(amount),
+                    'S' => chrono::Duration::seconds(amount),
+                    _ => return None,
+                };
+            }
+        }
+    }
+    Some(total)
+}
+
+/// Lê os VEVENTs de um calendário. Recorrências (`RRULE`) não são expandidas:
+/// apenas a primeira ocorrência é considerada, e os eventos recorrentes são
+/// listados no log para que não sumam sem aviso.
+pub fn parse_calendar(content: &str) -> Vec<ParsedEvent> {
+    // Desfaz a quebra de linhas longas (continuação começa com espaço ou tab)
+    let mut lines: Vec<String> = Vec::new();
+    for raw_line in content.lines() {
+        let raw_line = raw_line.trim_end_matches('\r');
+        match (raw_line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(continuation), Some(last)) => last.push_str(continuation),
+            _ => lines.push(raw_line.to_string()),
+        }
+    }
+
+    let mut events = Vec::new();
+    let mut current: Option<Vec<(String, String)>> = None;
+    let mut recurring: Vec<String> = Vec::new();
+    let mut time_zones: Vec<String> = Vec::new();
+
+    for line in lines {
+        let Some((key, value)) = line.split_once(':') else { continue };
+        // Parâmetros como TZID e VALUE=DATE não alteram a interpretação do valor
+        let mut parameters = key.split(';');
+        let name = parameters.next().unwrap_or_default().to_uppercase();
+        if let Some(tzid) = parameters.find_map(|parameter| parameter.strip_prefix("TZID=")) {
+            if current.is_some() && !time_zones.iter().any(|zone| zone == tzid) {
+                time_zones.push(tzid.to_string());
+            }
+        }
+
+        match (name.as_str(), value) {
+            ("BEGIN", "VEVENT") => current = Some(Vec::new()),
+            ("END", "VEVENT") => {
+                if let Some(properties) = current.take() {
+                    let event = build_event(&properties);
+                    if properties.iter().any(|(name, _)| name == "RRULE") {
+                        recurring.push(event.as_ref().map(|event| event.summary.clone()).unwrap_or_default());
+                    }
+                    events.extend(event);
+                }
+            }
+            _ => {
+                if let Some(properties) = current.as_mut() {
+                    properties.push((name, value.to_string()));
+                }
+            }
+        }
+    }
+
+    if !recurring.is_empty() {
+        println!(
+            "{} recurring calendar event(s) imported with the first occurrence only (RRULE is not expanded): {}",
+            recurring.len(),
+            recurring.join(", ")
+        );
+    }
+    if !time_zones.is_empty() {
+        println!("Calendar times with TZID {} were read as local time", time_zones.join(", "));
+    }
+
+    events
+}
+
+fn build_event(properties: &[(String, String)]) -> Option<ParsedEvent> {
+    let property = |name: &str| {
+        properties
+            .iter()
+            .find(|(property, _)| property == name)
+            .map(|(_, value)| value.as_str())
+    };
+
+    let (start, all_day) = parse_ics_datetime(property("DTSTART")?)?;
+    let end = match (property("DTEND").and_then(parse_ics_datetime), property("DURATION").and_then(parse_ics_duration)) {
+        (Some((end, _)), _) => end,
+        (None, Some(duration)) => start + duration,
+        (None, None) if all_day => start + chrono::Duration::days(1),
+        (None, None) => start,
+    };
+
+    Some(ParsedEvent {
+        uid: property("UID").unwrap_or_default().to_string(),
+        summary: unescape_text(property("SUMMARY").unwrap_or_default()),
+        categories: properties
+            .iter()
+            .filter(|(name, _)| name == "CATEGORIES")
+            .flat_map(|(_, value)| value.split(',').map(|category| unescape_text(category.trim())))
+            .collect(),
+        start,
+        end,
+        all_day,
+    })
+}
+
+#[cfg(feature = "app")]
+fn expected_end_from_state(state: &SharedState) -> Option<DateTime<Local>> {
+    let state_guard = state.lock().unwrap();
+    let status = state_guard.as_ref()?;
+    let time = chrono::NaiveTime::parse_from_str(&status.end_time, "%H:%M").ok()?;
+    local(Local::now().date_naive().and_time(time))
+}
+
+#[cfg(feature = "app")]
+fn write_calendar_file(
+    app: &AppHandle,
+    path: &str,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+    expected_end: Option<DateTime<Local>>,
+) -> Result<(), String> {
+    let history = history::load_history(app)?;
+    let events = work_events(&history, start_date, end_date, expected_end);
+
+    std::fs::write(path, write_calendar(&events))
+        .map_err(|e| format!("Failed to write {}: {}", path, e))
+}
+
+#[cfg(feature = "app")]
+/// Regrava o feed de calendário configurado, se houver. Chamado sempre que as
+/// marcações ou a saída prevista mudam.
+pub fn refresh_feed(app: &AppHandle, expected_end: Option<DateTime<Local>>) -> Result<(), String> {
+    let store = app.store("noponto.dat")
+        .map_err(|e| format!("Failed to get store: {}", e))?;
+
+    write_feed(store.as_ref(), expected_end)
+}
+
+/// Regrava o feed a partir do store; usado também pela CLI sem o app aberto.
+pub fn write_feed(store: &dyn KeyValueStore, expected_end: Option<DateTime<Local>>) -> Result<(), String> {
+    let Some(path) = store.get(FEED_PATH_KEY).and_then(|value| value.as_str().map(str::to_string)) else {
+        return Ok(());
+    };
+
+    let today = Local::now().date_naive();
+    let history = history::read_history(store)?;
+    let events = work_events(&history, today - chrono::Duration::days(FEED_DAYS_BACK), today, expected_end);
+    std::fs::write(&path, write_calendar(&events))
+        .map_err(|e| format!("Failed to write {}: {}", path, e))?;
+
+    println!("Calendar feed updated: {}", path);
+    Ok(())
+}
+
+#[cfg(feature = "app")]
+/// Regrava o feed após mudanças nas marcações, mantendo a saída prevista do
+/// monitoramento em andamento.
+pub fn refresh_feed_from_state(app: &AppHandle) -> Result<(), String> {
+    let expected_end = expected_end_from_state(app.state::<SharedState>().inner());
+    refresh_feed(app, expected_end)
+}
+
+#[cfg(feature = "app")]
+#[tauri::command]
+pub async fn export_work_calendar(
+    app: AppHandle,
+    state: State<'_, SharedState>,
+    path: String,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+) -> Result<(), String> {
+    let expected_end = expected_end_from_state(state.inner());
+    write_calendar_file(&app, &path, start_date, end_date, expected_end)
+}
+
+#[cfg(feature = "app")]
+/// Define o arquivo .ics mantido atualizado para assinatura em apps de
+/// calendário. `None` desativa o feed.
+#[tauri::command]
+pub async fn set_calendar_feed_path(
+    app: AppHandle,
+    state: State<'_, SharedState>,
+    path: Option<String>,
+) -> Result<(), String> {
+    let store = app.store("noponto.dat")
+        .map_err(|e| format!("Failed to get store: {}", e))?;
+
+    match &path {
+        Some(path) => store.set(FEED_PATH_KEY, serde_json::Value::String(path.clone())),
+        None => {
+            store.delete(FEED_PATH_KEY);
+        }
+    }
+
+    store.save()
+        .map_err(|e| format!("Failed to save store: {}", e))?;
+
+    refresh_feed(&app, expected_end_from_state(state.inner()))
+}
+
+#[cfg(feature = "app")]
+#[tauri::command]
+pub async fn get_calendar_feed_path(app: AppHandle) -> Result<Option<String>, String> {
+    let store = app.store("noponto.dat")
+        .map_err(|e| format!("Failed to get store: {}", e))?;
+
+    Ok(store.get(FEED_PATH_KEY).and_then(|value| value.as_str().map(str::to_string)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn datetime(value: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    #[test]
+    fn unfolds_continuation_lines() {
+        let content = "BEGIN:VCALENDAR\r\n\
+BEGIN:VEVENT\r\n\
+UID:folded@test\r\n\
+DTSTART:20250312T150000\r\n\
+DTEND:20250312T170000\r\n\
+SUMMARY:Consulta médica com o Dr. Silva\\, retorno dos exames de sangue\r\n  e avaliação\r\n\
+CATEGORIES:Saúde,\r\n\
+\tPessoal\r\n\
+END:VEVENT\r\n\
+END:VCALENDAR\r\n";
+
+        let events = parse_calendar(content);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].summary, "Consulta médica com o Dr. Silva, retorno dos exames de sangue e avaliação");
+        assert_eq!(events[0].categories, vec!["Saúde", "Pessoal"]);
+        assert_eq!(events[0].start, datetime("2025-03-12 15:00"));
+        assert_eq!(events[0].end, datetime("2025-03-12 17:00"));
+        assert!(!events[0].all_day);
+    }
+
+    #[test]
+    fn folded_output_is_read_back() {
+        let summary = "Trabalho ".repeat(12);
+        let folded = fold_line(&format!("SUMMARY:{}", summary));
+        assert!(folded.split("\r\n").all(|line| line.len() <= 75));
+
+        let content = format!("BEGIN:VEVENT\r\nDTSTART:20250312T080000\r\n{}\r\nEND:VEVENT\r\n", folded);
+        assert_eq!(parse_calendar(&content)[0].summary, summary);
+    }
+
+    #[test]
+    fn all_day_events_last_one_day() {
+        let content = "BEGIN:VEVENT
+UID:holiday@test
+DTSTART;VALUE=DATE:20250421
+SUMMARY:Feriado de Tiradentes
+END:VEVENT
+BEGIN:VEVENT
+UID:vacation@test
+DTSTART;VALUE=DATE:20250714
+DTEND;VALUE=DATE:20250719
+SUMMARY:Férias
+END:VEVENT";
+
+        let events = parse_calendar(content);
+        assert_eq!(events.len(), 2);
+        assert!(events.iter().all(|event| event.all_day));
+        assert_eq!(events[0].start, datetime("2025-04-21 00:00"));
+        assert_eq!(events[0].end, datetime("2025-04-22 00:00"));
+        assert_eq!(events[1].end, datetime("2025-07-19 00:00"));
+    }
+
+    #[test]
+    fn end_comes_from_dtend_or_duration() {
+        let content = "BEGIN:VEVENT
+UID:duration@test
+DTSTART:20250312T140000
+DURATION:PT1H30M
+SUMMARY:Reunião de planejamento
+END:VEVENT
+BEGIN:VEVENT
+UID:both@test
+DTSTART:20250312T160000
+DTEND:20250312T163000
+DURATION:PT2H
+SUMMARY:Daily
+END:VEVENT
+BEGIN:VEVENT
+UID:instant@test
+DTSTART;TZID=America/Sao_Paulo:20250312T180000
+SUMMARY:Lembrete
+END:VEVENT";
+
+        let events = parse_calendar(content);
+        assert_eq!(events[0].end, datetime("2025-03-12 15:30"));
+        // DTEND tem prioridade sobre DURATION
+        assert_eq!(events[1].end, datetime("2025-03-12 16:30"));
+        // TZID é lido como hora local
+        assert_eq!(events[2].start, datetime("2025-03-12 18:00"));
+        assert_eq!(events[2].end, events[2].start);
+    }
+
+    #[test]
+    fn recurring_events_keep_the_first_occurrence() {
+        let content = "BEGIN:VEVENT
+UID:weekly@test
+DTSTART:20250310T090000
+DTEND:20250310T093000
+RRULE:FREQ=WEEKLY;BYDAY=MO
+SUMMARY:Reunião semanal
+END:VEVENT";
+
+        let events = parse_calendar(content);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].start, datetime("2025-03-10 09:00"));
+    }
+
+    #[test]
+    fn parses_durations() {
+        assert_eq!(parse_ics_duration("PT1H30M"), Some(chrono::Duration::minutes(90)));
+        assert_eq!(parse_ics_duration("P1D"), Some(chrono::Duration::days(1)));
+        assert_eq!(parse_ics_duration("P1W"), Some(chrono::Duration::weeks(1)));
+        assert_eq!(parse_ics_duration("1H"), None);
+    }
+}
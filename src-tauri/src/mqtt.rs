@@ -0,0 +1,315 @@
+//! Publicação do status em um broker MQTT para automação residencial:
+//! `noponto/<usuário>/status` (JSON retido), `noponto/<usuário>/event/<evento>`
+//! e payloads de descoberta do Home Assistant.
+
+use rumqttc::{AsyncClient, Event, LastWill, MqttOptions, Outgoing, Packet, QoS};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_store::StoreExt;
+
+use crate::events;
+use crate::status::WorkPhase;
+use crate::webhooks::NotificationPayload;
+
+const SETTINGS_KEY: &str = "mqtt_settings";
+const RECONNECT_SECONDS: u64 = 5;
+/// Tempo para entregar o status final ao broker antes de encerrar o app.
+const STOP_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MqttSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    pub host: String,
+    #[serde(default = "default_port")]
+    pub port: u16,
+    #[serde(default)]
+    pub username: String,
+    #[serde(default)]
+    pub password: String,
+    /// Segmento do tópico (`noponto/<user>/...`); padrão é o usuário do sistema.
+    #[serde(default)]
+    pub user: Option<String>,
+    #[serde(default = "default_true")]
+    pub discovery: bool,
+    #[serde(default = "default_discovery_prefix")]
+    pub discovery_prefix: String,
+    /// Minutos finais da jornada sinalizados em `final_minutes`.
+    #[serde(default = "default_final_minutes")]
+    pub final_minutes: i64,
+}
+
+fn default_port() -> u16 {
+    1883
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_discovery_prefix() -> String {
+    "homeassistant".to_string()
+}
+
+fn default_final_minutes() -> i64 {
+    10
+}
+
+impl MqttSettings {
+    fn user(&self) -> String {
+        let user = self.user.clone()
+            .filter(|user| !user.trim().is_empty())
+            .or_else(|| std::env::var("USER").ok())
+            .or_else(|| std::env::var("USERNAME").ok())
+            .unwrap_or_else(|| "default".to_string());
+        user.trim().replace(['/', '+', '#', ' '], "_")
+    }
+
+    fn base_topic(&self) -> String {
+        format!("noponto/{}", self.user())
+    }
+}
+
+struct Connection {
+    client: AsyncClient,
+    settings: MqttSettings,
+}
+
+/// Cliente conectado e tarefa do loop de eventos, gerenciados pelo Tauri.
+#[derive(Default)]
+pub struct Mqtt {
+    connection: Mutex<Option<Connection>>,
+    task: Mutex<Option<tauri::async_runtime::JoinHandle<()>>>,
+}
+
+fn stopped_payload() -> Value {
+    json!({ "monitoring": false, "phase": "stopped", "final_minutes": false })
+}
+
+/// Status retido em `noponto/<usuário>/status`: o status do monitoramento
+/// mais o sinalizador `final_minutes`, com a janela configurada.
+fn status_payload(app: &AppHandle, settings: &MqttSettings) -> Value {
+    let Some(status) = events::latest_status(app) else { return stopped_payload() };
+
+    let final_minutes = matches!(status.phase, WorkPhase::Working | WorkPhase::FinalMinutes)
+        && status.remaining_minutes <= settings.final_minutes;
+
+    json!({
+        "monitoring": true,
+        "phase": status.phase,
+        "worked_minutes": status.worked_minutes,
+        "remaining_minutes": status.remaining_minutes,
+        "overtime_minutes": status.overtime_minutes,
+        "target_minutes": status.target_minutes,
+        "is_complete": status.is_complete,
+        "end_time": status.expected_end,
+        "final_minutes": final_minutes,
+    })
+}
+
+/// Configurações de descoberta do Home Assistant: sensores de tempo restante,
+/// hora extra, saída prevista e fase, e um binary_sensor para os minutos finais.
+fn discovery_payloads(settings: &MqttSettings) -> Vec<(String, Value)> {
+    let user = settings.user();
+    let base = settings.base_topic();
+    let node = format!("noponto_{}", user);
+    let device = json!({ "identifiers": [node], "name": format!("NoPonto ({})", user), "manufacturer": "NoPonto" });
+
+    let component = |kind: &str, key: &str, name: &str, extra: Value| {
+        let mut config = json!({
+            "name": name,
+            "unique_id": format!("{}_{}", node, key),
+            "state_topic": format!("{}/status", base),
+            "availability_topic": format!("{}/availability", base),
+            "device": device,
+        });
+        if let (Some(config), Value::Object(extra)) = (config.as_object_mut(), extra) {
+            config.extend(extra);
+        }
+        (format!("{}/{}/{}/{}/config", settings.discovery_prefix, kind, node, key), config)
+    };
+
+    vec![
+        component("sensor", "remaining", "Tempo restante", json!({
+            "value_template": "{{ value_json.remaining_minutes | default(0) }}",
+            "unit_of_measurement": "min",
+        })),
+        component("sensor", "overtime", "Hora extra", json!({
+            "value_template": "{{ value_json.overtime_minutes | default(0) }}",
+            "unit_of_measurement": "min",
+        })),
+        component("sensor", "end_time", "Saída prevista", json!({
+            "value_template": "{{ value_json.end_time | default('') }}",
+        })),
+        component("sensor", "phase", "Fase da jornada", json!({
+            "value_template": "{{ value_json.phase }}",
+        })),
+        component("binary_sensor", "final_minutes", "Minutos finais", json!({
+            "value_template": "{{ 'ON' if value_json.final_minutes else 'OFF' }}",
+        })),
+    ]
+}
+
+fn publish_on_connect(app: &AppHandle, client: &AsyncClient, settings: &MqttSettings) {
+    let base = settings.base_topic();
+    let _ = client.try_publish(format!("{}/availability", base), QoS::AtLeastOnce, true, "online");
+
+    if settings.discovery {
+        for (topic, config) in discovery_payloads(settings) {
+            let _ = client.try_publish(topic, QoS::AtLeastOnce, true, config.to_string());
+        }
+    }
+
+    let status = status_payload(app, settings);
+    let _ = client.try_publish(format!("{}/status", base), QoS::AtLeastOnce, true, status.to_string());
+}
+
+/// Publica o status atual (retido). Chamado a cada atualização do monitoramento.
+fn publish_status(app: &AppHandle) {
+    let Some(mqtt) = app.try_state::<Mqtt>() else { return };
+    let connection = mqtt.connection.lock().unwrap();
+    let Some(connection) = connection.as_ref() else { return };
+
+    let status = status_payload(app, &connection.settings);
+    let topic = format!("{}/status", connection.settings.base_topic());
+    if let Err(e) = connection.client.try_publish(topic, QoS::AtLeastOnce, true, status.to_string()) {
+        println!("Failed to publish MQTT status: {}", e);
+    }
+}
+
+/// Publica um evento em `noponto/<usuário>/event/<evento>`.
+fn publish_event(app: &AppHandle, payload: &NotificationPayload) {
+    let Some(mqtt) = app.try_state::<Mqtt>() else { return };
+    let connection = mqtt.connection.lock().unwrap();
+    let Some(connection) = connection.as_ref() else { return };
+
+    let topic = format!("{}/event/{}", connection.settings.base_topic(), payload.event.as_str());
+    let body = serde_json::to_string(payload).unwrap_or_default();
+    if let Err(e) = connection.client.try_publish(topic, QoS::AtLeastOnce, false, body) {
+        println!("Failed to publish MQTT event: {}", e);
+    }
+}
+
+/// Assina o barramento de eventos: status retido a cada mudança e tópicos de evento.
+pub fn listen(app: &AppHandle) {
+    events::listen(app, |app, event| {
+        if event.changes_status() {
+            publish_status(app);
+        }
+        if let Some(payload) = NotificationPayload::from_event(&event) {
+            publish_event(app, &payload);
+        }
+    });
+}
+
+pub fn load_mqtt_settings(app: &AppHandle) -> Result<Option<MqttSettings>, String> {
+    let store = app.store("noponto.dat")
+        .map_err(|e| format!("Failed to get store: {}", e))?;
+
+    let Some(encrypted) = store.get(SETTINGS_KEY) else { return Ok(None) };
+    let Some(encrypted) = encrypted.as_str() else { return Ok(None) };
+
+    let json = crate::decrypt_data(encrypted)?;
+    serde_json::from_str(&json)
+        .map(Some)
+        .map_err(|e| format!("Failed to parse MQTT settings: {}", e))
+}
+
+/// Conecta, reconecta ou desconecta conforme as configurações salvas.
+pub fn apply_settings(app: &AppHandle) -> Result<(), String> {
+    let mqtt = app.state::<Mqtt>();
+    // Ao derrubar a conexão anterior, o broker publica o "offline" do last will
+    if let Some(task) = mqtt.task.lock().unwrap().take() {
+        task.abort();
+    }
+    mqtt.connection.lock().unwrap().take();
+
+    let Some(settings) = load_mqtt_settings(app)? else { return Ok(()) };
+    if !settings.enabled {
+        return Ok(());
+    }
+
+    let base = settings.base_topic();
+    let mut options = MqttOptions::new(format!("noponto-{}", settings.user()), settings.host.clone(), settings.port);
+    options.set_keep_alive(Duration::from_secs(30));
+    options.set_last_will(LastWill::new(format!("{}/availability", base), "offline", QoS::AtLeastOnce, true));
+    if !settings.username.is_empty() {
+        options.set_credentials(settings.username.clone(), settings.password.clone());
+    }
+
+    let (client, mut eventloop) = AsyncClient::new(options, 32);
+    *mqtt.connection.lock().unwrap() = Some(Connection { client: client.clone(), settings: settings.clone() });
+
+    // O loop de eventos mantém a conexão e reconecta sozinho após falhas
+    let app = app.clone();
+    let task = tauri::async_runtime::spawn(async move {
+        loop {
+            match eventloop.poll().await {
+                Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                    println!("MQTT connected to {}:{}", settings.host, settings.port);
+                    publish_on_connect(&app, &client, &settings);
+                }
+                Ok(Event::Outgoing(Outgoing::Disconnect)) => break,
+                Ok(_) => {}
+                Err(e) => {
+                    println!("MQTT connection error: {}", e);
+                    tokio::time::sleep(Duration::from_secs(RECONNECT_SECONDS)).await;
+                }
+            }
+        }
+    });
+    *mqtt.task.lock().unwrap() = Some(task);
+
+    Ok(())
+}
+
+/// Publica o status "stopped" e a disponibilidade "offline" e desconecta. Na
+/// desconexão normal o broker não publica o last will, então o "offline" vai
+/// antes; a tarefa é abortada se a conexão não fechar a tempo.
+pub async fn stop(app: &AppHandle) {
+    let mqtt = app.state::<Mqtt>();
+    let connection = mqtt.connection.lock().unwrap().take();
+    let task = mqtt.task.lock().unwrap().take();
+    let Some(mut task) = task else { return };
+
+    if let Some(connection) = connection {
+        let base = connection.settings.base_topic();
+        let client = &connection.client;
+        let _ = client.try_publish(format!("{}/status", base), QoS::AtLeastOnce, true, stopped_payload().to_string());
+        let _ = client.try_publish(format!("{}/availability", base), QoS::AtLeastOnce, true, "offline");
+        let _ = client.try_disconnect();
+
+        if tokio::time::timeout(STOP_TIMEOUT, &mut task).await.is_ok() {
+            return;
+        }
+        println!("MQTT connection did not close in time");
+    }
+    task.abort();
+}
+
+#[tauri::command]
+pub async fn get_mqtt_settings(app: AppHandle) -> Result<Option<MqttSettings>, String> {
+    load_mqtt_settings(&app)
+}
+
+#[tauri::command]
+pub async fn save_mqtt_settings(app: AppHandle, settings: MqttSettings) -> Result<(), String> {
+    if settings.enabled && settings.host.trim().is_empty() {
+        return Err("Informe o endereço do broker MQTT".to_string());
+    }
+
+    let json = serde_json::to_string(&settings)
+        .map_err(|e| format!("Failed to serialize MQTT settings: {}", e))?;
+    let encrypted = crate::encrypt_data(&json)?;
+
+    let store = app.store("noponto.dat")
+        .map_err(|e| format!("Failed to get store: {}", e))?;
+    store.set(SETTINGS_KEY, Value::String(encrypted));
+    store.save()
+        .map_err(|e| format!("Failed to save store: {}", e))?;
+
+    apply_settings(&app)
+}
@@ -0,0 +1,69 @@
+mod pontomais;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Local, NaiveDate, NaiveTime};
+use serde::{Deserialize, Serialize};
+
+pub use pontomais::PontoMaisProvider;
+
+/// Uma marcação de ponto retornada (ou registrada) por um sistema de controle de ponto.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Punch {
+    pub date: NaiveDate,
+    pub time: NaiveTime,
+}
+
+impl Punch {
+    pub fn from_datetime(datetime: DateTime<Local>) -> Self {
+        Punch {
+            date: datetime.date_naive(),
+            time: datetime.time(),
+        }
+    }
+}
+
+/// Operações que um sistema de ponto precisa oferecer para ser usado pelo NoPonto.
+///
+/// Novos sistemas (Tangerino, Ahgora, Secullum...) implementam este trait e são
+/// registrados em `AVAILABLE_PROVIDERS` e `provider_from_config`.
+#[async_trait]
+pub trait TimeCardProvider: Send + Sync {
+    /// Identificador usado no campo `provider` da configuração.
+    fn id(&self) -> &'static str;
+
+    /// Busca as marcações entre `start` e `end` (inclusive), ordenadas por data e hora.
+    async fn fetch_punches(&self, start: NaiveDate, end: NaiveDate) -> Result<Vec<Punch>, String>;
+
+    /// Registra uma marcação agora e devolve o horário gravado pelo sistema.
+    async fn register_punch(&self) -> Result<Punch, String>;
+
+    /// Faz uma chamada simples à API e devolve a resposta bruta para diagnóstico.
+    async fn test_connection(&self) -> Result<String, String>;
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProviderInfo {
+    pub id: &'static str,
+    pub name: &'static str,
+}
+
+pub const DEFAULT_PROVIDER: &str = "pontomais";
+
+pub const AVAILABLE_PROVIDERS: &[ProviderInfo] = &[ProviderInfo {
+    id: "pontomais",
+    name: "PontoMais",
+}];
+
+/// Cria o provider indicado no campo `provider` da configuração salva.
+/// Configurações antigas, sem esse campo, continuam usando o PontoMais.
+pub fn provider_from_config(config: &str) -> Result<Box<dyn TimeCardProvider>, String> {
+    let config_data: serde_json::Value = serde_json::from_str(config)
+        .map_err(|e| format!("Invalid config JSON: {}", e))?;
+
+    let provider_id = config_data["provider"].as_str().unwrap_or(DEFAULT_PROVIDER);
+
+    match provider_id {
+        "pontomais" => Ok(Box::new(PontoMaisProvider::from_config(&config_data)?)),
+        other => Err(format!("Sistema de ponto não suportado: {}", other)),
+    }
+}
@@ -0,0 +1,278 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Local, NaiveDate, NaiveTime};
+use reqwest::{Client, RequestBuilder};
+use serde::{Deserialize, Serialize};
+
+use super::{Punch, TimeCardProvider};
+
+const API_URL: &str = "https://api.pontomais.com.br/api";
+
+#[derive(Debug, Serialize, Deserialize)]
+struct TimeCardResponse {
+    work_days: Vec<WorkDay>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct WorkDay {
+    #[serde(default)]
+    date: Option<String>,
+    time_cards: Vec<TimeCard>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct TimeCard {
+    time: String,
+}
+
+/// Resposta de `time_cards/register`, com a marcação como o PontoMais a gravou.
+#[derive(Debug, Deserialize)]
+struct RegisterResponse {
+    #[serde(alias = "untreated_time_card")]
+    time_card: Option<RegisteredTimeCard>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RegisteredTimeCard {
+    time: String,
+    #[serde(default)]
+    date: Option<String>,
+}
+
+pub struct PontoMaisProvider {
+    http: Client,
+    employee_id: String,
+    access_token: String,
+    client: String,
+    uid: String,
+    uuid: String,
+    latitude: f64,
+    longitude: f64,
+    address: String,
+}
+
+impl PontoMaisProvider {
+    pub fn from_config(config_data: &serde_json::Value) -> Result<Self, String> {
+        let field = |name: &str| -> Result<String, String> {
+            config_data[name]
+                .as_str()
+                .map(str::to_string)
+                .ok_or(format!("Missing {}", name))
+        };
+        let optional = |name: &str| config_data[name].as_str().filter(|s| !s.is_empty()).map(str::to_string);
+
+        Ok(PontoMaisProvider {
+            http: Client::new(),
+            employee_id: field("employeeId")?,
+            access_token: field("accessToken")?,
+            client: field("client")?,
+            uid: field("uid")?,
+            uuid: field("uuid")?,
+            latitude: config_data["latitude"].as_f64().unwrap_or(0.0),
+            longitude: config_data["longitude"].as_f64().unwrap_or(0.0),
+            address: optional("address").unwrap_or_default(),
+        })
+    }
+
+    /// Adiciona os cabeçalhos que a API espera receber do app web do PontoMais.
+    fn with_headers(&self, request: RequestBuilder) -> RequestBuilder {
+        request
+            .header("accept", "application/json, text/plain, */*")
+            .header("accept-language", "en-US,en;q=0.9,pt-BR;q=0.8,pt;q=0.7")
+            .header("access-token", &self.access_token)
+            .header("api-version", "2")
+            .header("client", &self.client)
+            .header("content-type", "application/json")
+            .header("dnt", "1")
+            .header("origin", "https://app2.pontomais.com.br")
+            .header("priority", "u=1, i")
+            .header("referer", "https://app2.pontomais.com.br/")
+            .header("sec-ch-ua", r#""Chromium";v="140", "Not=A?Brand";v="24", "Google Chrome";v="140""#)
+            .header("sec-ch-ua-mobile", "?0")
+            .header("sec-ch-ua-platform", r#""Windows""#)
+            .header("sec-fetch-dest", "empty")
+            .header("sec-fetch-mode", "cors")
+            .header("sec-fetch-site", "same-site")
+            .header("token", &self.access_token)
+            .header("uid", &self.uid)
+            .header("user-agent", "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/140.0.0.0 Safari/537.36")
+            .header("uuid", &self.uuid)
+    }
+
+    fn work_days_url(&self, start: NaiveDate, end: NaiveDate) -> String {
+        format!(
+            "{}/time_cards/work_days?employee_id={}&start_date={}&end_date={}&attributes=time_cards",
+            API_URL,
+            self.employee_id,
+            start.format("%Y-%m-%d"),
+            end.format("%Y-%m-%d")
+        )
+    }
+
+    async fn get_work_days(&self, start: NaiveDate, end: NaiveDate) -> Result<String, String> {
+        let url = self.work_days_url(start, end);
+
+        let response = self
+            .with_headers(self.http.get(&url))
+            .send()
+            .await
+            .map_err(|e| format!("Request failed: {}", e))?;
+
+        let status = response.status();
+        let response_text = response.text().await
+            .map_err(|e| format!("Failed to read response: {}", e))?;
+
+        if status.is_success() {
+            Ok(response_text)
+        } else {
+            Err(format!("API request failed with status {}: {}", status, response_text))
+        }
+    }
+}
+
+/// A API devolve `HH:MM`, mas algumas contas trazem também os segundos.
+fn parse_time(time: &str) -> Result<NaiveTime, String> {
+    NaiveTime::parse_from_str(time, "%H:%M:%S")
+        .or_else(|_| NaiveTime::parse_from_str(time, "%H:%M"))
+        .map_err(|e| format!("Invalid time card {}: {}", time, e))
+}
+
+/// Marcação registrada pelo servidor; sem horário legível na resposta, usa o
+/// horário local do envio.
+fn registered_punch(response_text: &str, sent_at: DateTime<Local>) -> Punch {
+    let time_card = serde_json::from_str::<RegisterResponse>(response_text)
+        .map_err(|e| format!("Failed to parse register response: {}", e))
+        .and_then(|response| response.time_card.ok_or_else(|| "Register response without time card".to_string()));
+
+    let punch = time_card.and_then(|time_card| {
+        let time = parse_time(&time_card.time)?;
+        let date = match time_card.date {
+            Some(date) => NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+                .map_err(|e| format!("Invalid time card date {}: {}", date, e))?,
+            None => sent_at.date_naive(),
+        };
+        Ok(Punch { date, time })
+    });
+
+    punch.unwrap_or_else(|e| {
+        println!("{}; using the local time of the punch", e);
+        Punch::from_datetime(sent_at)
+    })
+}
+
+#[async_trait]
+impl TimeCardProvider for PontoMaisProvider {
+    fn id(&self) -> &'static str {
+        "pontomais"
+    }
+
+    async fn fetch_punches(&self, start: NaiveDate, end: NaiveDate) -> Result<Vec<Punch>, String> {
+        let response_text = self.get_work_days(start, end).await?;
+
+        let time_card_response: TimeCardResponse = serde_json::from_str(&response_text)
+            .map_err(|e| format!("Failed to parse JSON response: {}", e))?;
+
+        let mut punches = Vec::new();
+
+        for work_day in &time_card_response.work_days {
+            // Consultas de um único dia nem sempre trazem a data do work_day
+            let date = match &work_day.date {
+                Some(date) => NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                    .map_err(|e| format!("Invalid work day date {}: {}", date, e))?,
+                None if start == end => start,
+                None => {
+                    println!("Skipping {} time card(s) without a work day date", work_day.time_cards.len());
+                    continue;
+                }
+            };
+
+            for time_card in &work_day.time_cards {
+                punches.push(Punch { date, time: parse_time(&time_card.time)? });
+            }
+        }
+
+        punches.sort();
+        Ok(punches)
+    }
+
+    async fn register_punch(&self) -> Result<Punch, String> {
+        let body = serde_json::json!({
+            "time_card": {
+                "latitude": self.latitude,
+                "longitude": self.longitude,
+                "address": self.address,
+                "reference_id": null,
+                "original_latitude": self.latitude,
+                "original_longitude": self.longitude,
+                "original_address": self.address,
+                "location_edited": false,
+                "accuracy": 100,
+            },
+            "_path": "/meu_ponto/registro_de_ponto",
+            "_device": {
+                "browser": { "name": "Chrome", "version": "140.0.0.0" }
+            },
+        });
+
+        let sent_at = Local::now();
+        let response = self
+            .with_headers(self.http.post(format!("{}/time_cards/register", API_URL)))
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| format!("Request failed: {}", e))?;
+
+        let status = response.status();
+        let response_text = response.text().await.unwrap_or_default();
+        if !status.is_success() {
+            return Err(format!("API request failed with status {}: {}", status, response_text));
+        }
+
+        Ok(registered_punch(&response_text, sent_at))
+    }
+
+    async fn test_connection(&self) -> Result<String, String> {
+        let today = Local::now().date_naive();
+        self.get_work_days(today, today).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn sent_at() -> DateTime<Local> {
+        Local.with_ymd_and_hms(2025, 3, 12, 8, 1, 30).unwrap()
+    }
+
+    fn time(value: &str) -> NaiveTime {
+        NaiveTime::parse_from_str(value, "%H:%M:%S").unwrap()
+    }
+
+    #[test]
+    fn parses_times_with_and_without_seconds() {
+        assert_eq!(parse_time("08:02"), Ok(time("08:02:00")));
+        assert_eq!(parse_time("08:02:45"), Ok(time("08:02:45")));
+        assert!(parse_time("8h02").is_err());
+    }
+
+    #[test]
+    fn uses_the_time_registered_by_the_server() {
+        let punch = registered_punch(r#"{"untreated_time_card": {"time": "08:02", "date": "2025-03-12"}}"#, sent_at());
+        assert_eq!(punch, Punch { date: sent_at().date_naive(), time: time("08:02:00") });
+
+        let punch = registered_punch(r#"{"time_card": {"time": "23:59:58", "date": "2025-03-11"}}"#, sent_at());
+        assert_eq!(punch.date, NaiveDate::from_ymd_opt(2025, 3, 11).unwrap());
+        assert_eq!(punch.time, time("23:59:58"));
+
+        let punch = registered_punch(r#"{"time_card": {"time": "08:03:10"}}"#, sent_at());
+        assert_eq!(punch, Punch { date: sent_at().date_naive(), time: time("08:03:10") });
+    }
+
+    #[test]
+    fn falls_back_to_the_local_time() {
+        for body in ["", "{}", r#"{"time_card": {"time": "invalid"}}"#, r#"{"time_card": {"time": "08:02", "date": "12/03"}}"#] {
+            assert_eq!(registered_punch(body, sent_at()), Punch::from_datetime(sent_at()), "{}", body);
+        }
+    }
+}
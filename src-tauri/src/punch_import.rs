@@ -0,0 +1,487 @@
+use calamine::{open_workbook_auto, Data, Reader};
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime, Timelike};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+use tauri::AppHandle;
+
+use crate::history::{self, PunchHistory, PunchKind, PunchSource};
+
+/// Como encontrar data, hora e tipo de marcação nas colunas da planilha.
+///
+/// As colunas podem ser indicadas pelo nome do cabeçalho, pela letra (`"B"`)
+/// ou pela posição começando em 1 (`"2"`).
+#[derive(Debug, Clone, Deserialize)]
+pub struct ColumnMapping {
+    pub date_column: String,
+    pub time_column: String,
+    #[serde(default)]
+    pub type_column: Option<String>,
+    #[serde(default = "default_date_format")]
+    pub date_format: String,
+    #[serde(default = "default_time_format")]
+    pub time_format: String,
+    /// Separador do CSV; detectado pela primeira linha quando omitido.
+    #[serde(default)]
+    pub delimiter: Option<char>,
+    #[serde(default = "default_has_header")]
+    pub has_header: bool,
+    /// Aba da planilha XLSX; a primeira aba é usada quando omitida.
+    #[serde(default)]
+    pub sheet: Option<String>,
+}
+
+fn default_date_format() -> String {
+    "%d/%m/%Y".to_string()
+}
+
+fn default_time_format() -> String {
+    "%H:%M".to_string()
+}
+
+fn default_has_header() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportRow {
+    pub line: usize,
+    pub date: Option<NaiveDate>,
+    pub time: Option<NaiveTime>,
+    pub kind: Option<PunchKind>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportConflict {
+    pub date: NaiveDate,
+    pub existing: Vec<NaiveTime>,
+    pub imported: Vec<NaiveTime>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportPreview {
+    pub rows: Vec<ImportRow>,
+    pub valid_rows: usize,
+    pub invalid_rows: usize,
+    pub days: BTreeMap<NaiveDate, Vec<NaiveTime>>,
+    pub conflicts: Vec<ImportConflict>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportSummary {
+    pub imported_days: usize,
+    pub imported_punches: usize,
+    pub skipped_days: Vec<NaiveDate>,
+    pub invalid_rows: usize,
+}
+
+fn is_spreadsheet(path: &Path) -> bool {
+    matches!(
+        path.extension().and_then(|ext| ext.to_str()).map(str::to_lowercase).as_deref(),
+        Some("xlsx" | "xlsm" | "xls" | "ods")
+    )
+}
+
+/// Lê o arquivo como uma matriz de textos, incluindo a linha de cabeçalho.
+fn read_table(path: &Path, mapping: &ColumnMapping) -> Result<Vec<Vec<String>>, String> {
+    if is_spreadsheet(path) {
+        read_spreadsheet(path, mapping.sheet.as_deref())
+    } else {
+        read_csv(path, mapping.delimiter)
+    }
+}
+
+fn read_csv(path: &Path, delimiter: Option<char>) -> Result<Vec<Vec<String>>, String> {
+    let bytes = std::fs::read(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+
+    // Exportações de sistemas de RH costumam vir em Latin-1 em vez de UTF-8
+    let content = String::from_utf8(bytes)
+        .unwrap_or_else(|e| e.into_bytes().iter().map(|&b| b as char).collect());
+    let content = content.trim_start_matches('\u{feff}');
+
+    let delimiter = delimiter.unwrap_or_else(|| {
+        let first_line = content.lines().next().unwrap_or_default();
+        [';', '\t', ',']
+            .into_iter()
+            .max_by_key(|candidate| first_line.matches(*candidate).count())
+            .unwrap_or(',')
+    });
+    if !delimiter.is_ascii() {
+        return Err(format!("Separador inválido: '{}' (use um caractere ASCII)", delimiter));
+    }
+
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter as u8)
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(content.as_bytes());
+
+    reader
+        .records()
+        .map(|record| {
+            record
+                .map(|record| record.iter().map(|field| field.trim().to_string()).collect())
+                .map_err(|e| format!("Invalid CSV: {}", e))
+        })
+        .collect()
+}
+
+fn read_spreadsheet(path: &Path, sheet: Option<&str>) -> Result<Vec<Vec<String>>, String> {
+    let mut workbook = open_workbook_auto(path)
+        .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+
+    let sheet_name = match sheet {
+        Some(name) => name.to_string(),
+        None => workbook
+            .sheet_names()
+            .first()
+            .cloned()
+            .ok_or("A planilha não possui abas")?,
+    };
+
+    let range = workbook
+        .worksheet_range(&sheet_name)
+        .map_err(|e| format!("Failed to read sheet {}: {}", sheet_name, e))?;
+
+    Ok(range
+        .rows()
+        .map(|row| row.iter().map(cell_to_string).collect())
+        .collect())
+}
+
+fn cell_to_string(cell: &Data) -> String {
+    match cell {
+        Data::DateTime(datetime) => datetime
+            .as_datetime()
+            .map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string())
+            .unwrap_or_default(),
+        other => other.to_string().trim().to_string(),
+    }
+}
+
+/// Converte a referência de coluna em índice (base 0). Letras de coluna são
+/// sempre maiúsculas (`"B"`, `"AA"`) e, havendo cabeçalho, precisam existir
+/// nele; assim um nome de cabeçalho digitado errado vira erro em vez de
+/// apontar para outra coluna.
+fn resolve_column(reference: &str, header: Option<&[String]>) -> Result<usize, String> {
+    let reference = reference.trim();
+
+    if let Some(header) = header {
+        if let Some(index) = header.iter().position(|name| name.trim().eq_ignore_ascii_case(reference)) {
+            return Ok(index);
+        }
+    }
+
+    let index = if let Ok(position) = reference.parse::<usize>() {
+        position.checked_sub(1)
+    } else if !reference.is_empty() && reference.len() <= 3 && reference.chars().all(|c| c.is_ascii_uppercase()) {
+        let index = reference
+            .bytes()
+            .fold(0usize, |acc, b| acc * 26 + (b - b'A' + 1) as usize);
+        Some(index - 1)
+    } else {
+        None
+    };
+
+    match (index, header) {
+        (Some(index), Some(header)) if index >= header.len() => {
+            Err(format!("Coluna não encontrada: {} (a planilha tem {} colunas)", reference, header.len()))
+        }
+        (Some(index), _) => Ok(index),
+        (None, _) => Err(format!("Coluna não encontrada: {}", reference)),
+    }
+}
+
+fn parse_date(value: &str, mapping: &ColumnMapping) -> Option<NaiveDate> {
+    [mapping.date_format.as_str(), "%Y-%m-%d", "%d/%m/%Y"]
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(value, format).ok())
+        .or_else(|| parse_datetime(value, mapping).map(|dt| dt.date()))
+}
+
+fn parse_time(value: &str, mapping: &ColumnMapping) -> Option<NaiveTime> {
+    [mapping.time_format.as_str(), "%H:%M:%S", "%H:%M"]
+        .iter()
+        .find_map(|format| NaiveTime::parse_from_str(value, format).ok())
+        .or_else(|| parse_datetime(value, mapping).map(|dt| dt.time()))
+        .and_then(|time| time.with_second(0))
+}
+
+/// Células que trazem data e hora juntas (comum em XLSX).
+fn parse_datetime(value: &str, mapping: &ColumnMapping) -> Option<NaiveDateTime> {
+    let custom = format!("{} {}", mapping.date_format, mapping.time_format);
+    [custom.as_str(), "%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S", "%d/%m/%Y %H:%M:%S", "%d/%m/%Y %H:%M"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+}
+
+fn parse_kind(value: &str) -> Option<PunchKind> {
+    match value.trim().to_lowercase().as_str() {
+        "entrada" | "e" | "in" | "entry" | "1" => Some(PunchKind::Entrada),
+        "saída" | "saida" | "s" | "out" | "exit" | "2" => Some(PunchKind::Saida),
+        _ => None,
+    }
+}
+
+fn parse_rows(table: &[Vec<String>], mapping: &ColumnMapping) -> Result<Vec<ImportRow>, String> {
+    let header = if mapping.has_header { table.first().map(Vec::as_slice) } else { None };
+    let skip = usize::from(mapping.has_header);
+
+    let date_index = resolve_column(&mapping.date_column, header)?;
+    let time_index = resolve_column(&mapping.time_column, header)?;
+    let type_index = mapping
+        .type_column
+        .as_deref()
+        .filter(|column| !column.trim().is_empty())
+        .map(|column| resolve_column(column, header))
+        .transpose()?;
+
+    let mut rows = Vec::new();
+
+    for (index, record) in table.iter().enumerate().skip(skip) {
+        if record.iter().all(|field| field.is_empty()) {
+            continue;
+        }
+
+        let field = |column: usize| record.get(column).map(String::as_str).unwrap_or_default();
+        let date = parse_date(field(date_index), mapping);
+        let time = parse_time(field(time_index), mapping);
+        let kind = type_index.and_then(|column| parse_kind(field(column)));
+
+        let error = if date.is_none() {
+            Some(format!("Data inválida: '{}'", field(date_index)))
+        } else if time.is_none() {
+            Some(format!("Horário inválido: '{}'", field(time_index)))
+        } else if let (Some(column), None) = (type_index, kind) {
+            Some(format!("Tipo de marcação inválido: '{}'", field(column)))
+        } else {
+            None
+        };
+
+        rows.push(ImportRow { line: index + 1, date, time, kind, error });
+    }
+
+    validate_days(&mut rows);
+    Ok(rows)
+}
+
+/// Verifica duplicidades e, quando a planilha informa o tipo, se entradas e
+/// saídas se alternam ao longo do dia.
+fn validate_days(rows: &mut [ImportRow]) {
+    let mut by_day: BTreeMap<NaiveDate, Vec<usize>> = BTreeMap::new();
+    for (index, row) in rows.iter().enumerate() {
+        if let (None, Some(date)) = (&row.error, row.date) {
+            by_day.entry(date).or_default().push(index);
+        }
+    }
+
+    for indexes in by_day.values_mut() {
+        indexes.sort_by_key(|&index| rows[index].time);
+
+        // Só as marcações aceitas contam para a alternância entrada/saída
+        let mut accepted = 0;
+        let mut last_time = None;
+
+        for &index in indexes.iter() {
+            if last_time.is_some() && last_time == rows[index].time {
+                rows[index].error = Some("Marcação duplicada".to_string());
+                continue;
+            }
+
+            let expected = PunchKind::for_position(accepted);
+            if rows[index].kind.is_some_and(|kind| kind != expected) {
+                rows[index].error = Some(format!(
+                    "Esperada marcação de {}",
+                    if expected == PunchKind::Entrada { "entrada" } else { "saída" }
+                ));
+                continue;
+            }
+
+            accepted += 1;
+            last_time = rows[index].time;
+        }
+    }
+}
+
+fn build_preview(rows: Vec<ImportRow>, history: &PunchHistory) -> ImportPreview {
+    let mut days: BTreeMap<NaiveDate, Vec<NaiveTime>> = BTreeMap::new();
+    for row in rows.iter().filter(|row| row.error.is_none()) {
+        if let (Some(date), Some(time)) = (row.date, row.time) {
+            days.entry(date).or_default().push(time);
+        }
+    }
+    for times in days.values_mut() {
+        times.sort();
+    }
+
+    let conflicts = days
+        .iter()
+        .filter_map(|(&date, imported)| {
+            let existing = history.times(date);
+            (!existing.is_empty() && existing != *imported).then(|| ImportConflict {
+                date,
+                existing,
+                imported: imported.clone(),
+            })
+        })
+        .collect();
+
+    let invalid_rows = rows.iter().filter(|row| row.error.is_some()).count();
+
+    ImportPreview {
+        valid_rows: rows.len() - invalid_rows,
+        invalid_rows,
+        rows,
+        days,
+        conflicts,
+    }
+}
+
+fn load_preview(app: &AppHandle, path: &str, mapping: &ColumnMapping) -> Result<ImportPreview, String> {
+    let table = read_table(Path::new(path), mapping)?;
+    let rows = parse_rows(&table, mapping)?;
+    let history = history::load_history(app)?;
+    Ok(build_preview(rows, &history))
+}
+
+#[tauri::command]
+pub async fn preview_punch_import(app: AppHandle, path: String, mapping: ColumnMapping) -> Result<ImportPreview, String> {
+    load_preview(&app, &path, &mapping)
+}
+
+/// Grava as marcações válidas no histórico local. Dias em conflito com o
+/// histórico só são substituídos quando `overwrite` é verdadeiro.
+#[tauri::command]
+pub async fn import_punches(
+    app: AppHandle,
+    path: String,
+    mapping: ColumnMapping,
+    overwrite: bool,
+) -> Result<ImportSummary, String> {
+    let preview = load_preview(&app, &path, &mapping)?;
+    let mut history = history::load_history(&app)?;
+
+    let skipped_days: Vec<NaiveDate> = if overwrite {
+        Vec::new()
+    } else {
+        preview.conflicts.iter().map(|conflict| conflict.date).collect()
+    };
+
+    let mut imported_days = 0;
+    let mut imported_punches = 0;
+    for (date, times) in &preview.days {
+        if skipped_days.contains(date) {
+            continue;
+        }
+        history.set_day(*date, times, PunchSource::Import);
+        imported_days += 1;
+        imported_punches += times.len();
+    }
+
+    history::save_history(&app, &history)?;
+    if let Err(e) = crate::ics::refresh_feed_from_state(&app) {
+        println!("Failed to update calendar feed: {}", e);
+    }
+
+    println!("Imported {} punches over {} days from {}", imported_punches, imported_days, path);
+
+    Ok(ImportSummary {
+        imported_days,
+        imported_punches,
+        skipped_days,
+        invalid_rows: preview.invalid_rows,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mapping(type_column: Option<&str>) -> ColumnMapping {
+        ColumnMapping {
+            date_column: "Data".to_string(),
+            time_column: "Hora".to_string(),
+            type_column: type_column.map(str::to_string),
+            date_format: default_date_format(),
+            time_format: default_time_format(),
+            delimiter: None,
+            has_header: true,
+            sheet: None,
+        }
+    }
+
+    fn table(rows: &[&[&str]]) -> Vec<Vec<String>> {
+        rows.iter().map(|row| row.iter().map(|field| field.to_string()).collect()).collect()
+    }
+
+    fn errors(rows: &[ImportRow]) -> Vec<Option<&str>> {
+        rows.iter().map(|row| row.error.as_deref()).collect()
+    }
+
+    #[test]
+    fn resolves_columns_by_name_letter_and_position() {
+        let header = table(&[&["Data", "Hora", "Tipo"]]).remove(0);
+
+        assert_eq!(resolve_column("hora", Some(&header)), Ok(1));
+        assert_eq!(resolve_column("C", Some(&header)), Ok(2));
+        assert_eq!(resolve_column("1", Some(&header)), Ok(0));
+        assert_eq!(resolve_column("AA", None), Ok(26));
+    }
+
+    #[test]
+    fn rejects_unknown_columns() {
+        let header = table(&[&["Data", "Hora", "Tipo"]]).remove(0);
+
+        assert!(resolve_column("Dia", Some(&header)).is_err());
+        assert!(resolve_column("D", Some(&header)).is_err());
+        assert!(resolve_column("0", Some(&header)).is_err());
+        assert!(resolve_column("hr", None).is_err());
+    }
+
+    #[test]
+    fn flags_duplicates_without_shifting_the_alternation() {
+        let rows = parse_rows(
+            &table(&[
+                &["Data", "Hora", "Tipo"],
+                &["02/03/2026", "08:00", "entrada"],
+                &["02/03/2026", "08:00", "entrada"],
+                &["02/03/2026", "12:00", "saída"],
+                &["02/03/2026", "13:00", "entrada"],
+                &["02/03/2026", "17:00", "saída"],
+            ]),
+            &mapping(Some("Tipo")),
+        )
+        .unwrap();
+
+        assert_eq!(errors(&rows), vec![None, Some("Marcação duplicada"), None, None, None]);
+    }
+
+    #[test]
+    fn flags_punches_out_of_alternation() {
+        let rows = parse_rows(
+            &table(&[
+                &["Data", "Hora", "Tipo"],
+                &["02/03/2026", "08:00", "entrada"],
+                &["02/03/2026", "12:00", "entrada"],
+                &["02/03/2026", "12:05", "saída"],
+            ]),
+            &mapping(Some("Tipo")),
+        )
+        .unwrap();
+
+        assert_eq!(errors(&rows), vec![None, Some("Esperada marcação de saída"), None]);
+    }
+
+    #[test]
+    fn reports_invalid_cells() {
+        let rows = parse_rows(
+            &table(&[&["Data", "Hora"], &["32/03/2026", "08:00"], &["02/03/2026", "25:00"]]),
+            &mapping(None),
+        )
+        .unwrap();
+
+        assert_eq!(errors(&rows), vec![Some("Data inválida: '32/03/2026'"), Some("Horário inválido: '25:00'")]);
+    }
+}
@@ -0,0 +1,204 @@
+//! Notificações push no celular via ntfy ou Gotify (públicos ou auto-hospedados),
+//! para quando o aviso na tela não é visto. Os canais são salvos criptografados,
+//! por conterem tokens de acesso.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+
+use crate::delivery::{self, Delivery};
+use crate::events;
+use crate::webhooks::{NotificationEvent, NotificationPayload};
+
+const CONFIG_KEY: &str = "push_channels";
+const DELIVERIES_KEY: &str = "push_deliveries";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PushService {
+    Ntfy,
+    Gotify,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PushChannel {
+    pub name: String,
+    pub service: PushService,
+    /// Endereço do servidor (ex.: `https://ntfy.sh` ou `http://localhost:8080`).
+    pub url: String,
+    /// Tópico do ntfy; ignorado pelo Gotify.
+    #[serde(default)]
+    pub topic: String,
+    /// Token de acesso do ntfy ou token de aplicativo do Gotify.
+    #[serde(default)]
+    pub token: Option<String>,
+    #[serde(default = "default_events")]
+    pub events: Vec<NotificationEvent>,
+    /// Prioridade por evento: 1 a 5 no ntfy, 0 a 10 no Gotify.
+    #[serde(default)]
+    pub priorities: HashMap<NotificationEvent, u8>,
+    /// Tags (emojis) do ntfy por evento.
+    #[serde(default)]
+    pub tags: HashMap<NotificationEvent, Vec<String>>,
+    /// Tentativas por notificação, com espera dobrando a cada falha.
+    #[serde(default = "delivery::default_max_attempts")]
+    pub max_attempts: u32,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_events() -> Vec<NotificationEvent> {
+    vec![NotificationEvent::Warning, NotificationEvent::Complete]
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl PushChannel {
+    fn priority(&self, event: NotificationEvent) -> u8 {
+        if let Some(priority) = self.priorities.get(&event) {
+            return *priority;
+        }
+        match (self.service, event) {
+            (PushService::Ntfy, NotificationEvent::Warning | NotificationEvent::Complete) => 4,
+            (PushService::Ntfy, _) => 3,
+            (PushService::Gotify, NotificationEvent::Warning | NotificationEvent::Complete) => 8,
+            (PushService::Gotify, _) => 5,
+        }
+    }
+
+    /// Requisição no formato da API JSON de cada serviço.
+    fn request(&self, payload: &NotificationPayload) -> (delivery::Request, Value) {
+        let base = self.url.trim_end_matches('/');
+        let priority = self.priority(payload.event);
+
+        match self.service {
+            PushService::Ntfy => {
+                let tags = self.tags.get(&payload.event).cloned().unwrap_or_default();
+                let body = json!({
+                    "topic": self.topic,
+                    "title": payload.title,
+                    "message": payload.message,
+                    "priority": priority,
+                    "tags": tags,
+                });
+                let mut request = delivery::Request::post(&self.name, base);
+                if let Some(token) = &self.token {
+                    request.headers.insert("Authorization".to_string(), format!("Bearer {}", token));
+                }
+                (request, body)
+            }
+            PushService::Gotify => {
+                let body = json!({
+                    "title": payload.title,
+                    "message": payload.message,
+                    "priority": priority,
+                });
+                let mut request = delivery::Request::post(&self.name, format!("{}/message", base));
+                if let Some(token) = &self.token {
+                    request.headers.insert("X-Gotify-Key".to_string(), token.clone());
+                }
+                (request, body)
+            }
+        }
+    }
+}
+
+async fn send(channel: &PushChannel, payload: &NotificationPayload, max_attempts: u32) -> Delivery {
+    let (request, body) = channel.request(payload);
+    delivery::send_with_retries(&request, payload.event, Ok(body), max_attempts).await
+}
+
+pub fn load_push_channels(app: &AppHandle) -> Result<Vec<PushChannel>, String> {
+    let store = app.store("noponto.dat")
+        .map_err(|e| format!("Failed to get store: {}", e))?;
+
+    let Some(encrypted) = store.get(CONFIG_KEY) else { return Ok(Vec::new()) };
+    let Some(encrypted) = encrypted.as_str() else { return Ok(Vec::new()) };
+
+    let json = crate::decrypt_data(encrypted)?;
+    serde_json::from_str(&json)
+        .map_err(|e| format!("Failed to parse push channels: {}", e))
+}
+
+/// Envia o evento, em segundo plano, aos canais inscritos nele.
+fn dispatch(app: &AppHandle, payload: NotificationPayload) {
+    let channels = match load_push_channels(app) {
+        Ok(channels) => channels,
+        Err(e) => {
+            println!("Failed to load push channels: {}", e);
+            return;
+        }
+    };
+
+    for channel in channels.into_iter().filter(|channel| channel.enabled && channel.events.contains(&payload.event)) {
+        let app = app.clone();
+        let payload = payload.clone();
+        tauri::async_runtime::spawn(async move {
+            let delivery = send(&channel, &payload, channel.max_attempts).await;
+            if !delivery.success {
+                println!("Push notification to {} failed: {}", channel.name, delivery.error.as_deref().unwrap_or(""));
+            }
+            if let Err(e) = delivery::record(&app, DELIVERIES_KEY, delivery) {
+                println!("Failed to record push delivery: {}", e);
+            }
+        });
+    }
+}
+
+/// Assina o barramento de eventos e envia os avisos ao celular.
+pub fn listen(app: &AppHandle) {
+    events::listen(app, |app, event| {
+        if let Some(payload) = NotificationPayload::from_event(&event) {
+            dispatch(app, payload);
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn get_push_channels(app: AppHandle) -> Result<Vec<PushChannel>, String> {
+    load_push_channels(&app)
+}
+
+#[tauri::command]
+pub async fn save_push_channels(app: AppHandle, channels: Vec<PushChannel>) -> Result<(), String> {
+    for channel in &channels {
+        reqwest::Url::parse(&channel.url)
+            .map_err(|e| format!("URL inválida para o canal {}: {}", channel.name, e))?;
+        if channel.service == PushService::Ntfy && channel.topic.trim().is_empty() {
+            return Err(format!("Informe o tópico do ntfy para o canal {}", channel.name));
+        }
+    }
+
+    let json = serde_json::to_string(&channels)
+        .map_err(|e| format!("Failed to serialize push channels: {}", e))?;
+    let encrypted = crate::encrypt_data(&json)?;
+
+    let store = app.store("noponto.dat")
+        .map_err(|e| format!("Failed to get store: {}", e))?;
+    store.set(CONFIG_KEY, Value::String(encrypted));
+
+    store.save()
+        .map_err(|e| format!("Failed to save store: {}", e))
+}
+
+#[tauri::command]
+pub async fn get_push_deliveries(app: AppHandle) -> Result<Vec<Delivery>, String> {
+    delivery::load_log(&app, DELIVERIES_KEY)
+}
+
+#[tauri::command]
+pub async fn test_push_channel(app: AppHandle, channel: PushChannel) -> Result<Delivery, String> {
+    let payload = NotificationPayload::new(
+        NotificationEvent::Complete,
+        "🧪 Teste do NoPonto",
+        "Se você recebeu esta notificação, o canal está configurado corretamente.",
+    );
+
+    let delivery = send(&channel, &payload, 1).await;
+    delivery::record(&app, DELIVERIES_KEY, delivery.clone())?;
+    Ok(delivery)
+}
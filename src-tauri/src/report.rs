@@ -0,0 +1,290 @@
+use chrono::{Datelike, Duration, NaiveDate, NaiveTime, Timelike};
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::history::{self, PunchHistory};
+use crate::schedule;
+use crate::timesheet::{self, DaySummary};
+
+/// Intervalo mínimo para jornadas acima de 6 horas (CLT, art. 71).
+const MIN_LUNCH_MINUTES: i64 = 60;
+/// Jornada máxima com horas extras: 8h + 2h (CLT, art. 59).
+const MAX_DAILY_MINUTES: i64 = 10 * 60;
+/// Descanso mínimo entre jornadas (CLT, art. 66).
+const MIN_REST_MINUTES: i64 = 11 * 60;
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportPeriod {
+    Week,
+    Month,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Violation {
+    /// Número ímpar de marcações: falta uma entrada ou saída.
+    IncompletePunches,
+    ShortLunch,
+    LongDay,
+    ShortRest,
+}
+
+impl Violation {
+    pub fn description(self) -> &'static str {
+        match self {
+            Violation::IncompletePunches => "marcações incompletas",
+            Violation::ShortLunch => "intervalo menor que 1h",
+            Violation::LongDay => "jornada acima de 10h",
+            Violation::ShortRest => "descanso menor que 11h entre jornadas",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DayViolations {
+    pub date: NaiveDate,
+    pub violations: Vec<Violation>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PeriodReport {
+    /// "Semana de 06/10/2025" ou "10/2025".
+    pub label: String,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub worked_minutes: i64,
+    pub target_minutes: i64,
+    pub balance_minutes: i64,
+    pub overtime_minutes: i64,
+    pub days_worked: usize,
+    pub average_start: Option<String>,
+    pub average_end: Option<String>,
+    pub average_lunch_minutes: Option<i64>,
+    pub violation_days: Vec<DayViolations>,
+}
+
+fn average_time(times: &[NaiveTime]) -> Option<String> {
+    if times.is_empty() {
+        return None;
+    }
+    let seconds: i64 = times.iter().map(|time| time.num_seconds_from_midnight() as i64).sum();
+    let average = seconds / times.len() as i64;
+    Some(format!("{:02}:{:02}", average / 3600, average % 3600 / 60))
+}
+
+fn day_violations(history: &PunchHistory, day: &DaySummary) -> Vec<Violation> {
+    let times = history.times(day.date);
+    let mut violations = Vec::new();
+    if times.is_empty() {
+        return violations;
+    }
+
+    if !times.len().is_multiple_of(2) {
+        violations.push(Violation::IncompletePunches);
+    }
+
+    // Intervalo entre a primeira saída e a segunda entrada
+    if day.worked_minutes > 6 * 60 {
+        let lunch = times.get(1).zip(times.get(2)).map(|(out, back)| (*back - *out).num_minutes());
+        if lunch.is_none_or(|lunch| lunch < MIN_LUNCH_MINUTES) {
+            violations.push(Violation::ShortLunch);
+        }
+    }
+
+    if day.worked_minutes > MAX_DAILY_MINUTES {
+        violations.push(Violation::LongDay);
+    }
+
+    let previous = day.date - Duration::days(1);
+    let previous_times = history.times(previous);
+    if let (Some(last_exit), Some(first_entry)) = (previous_times.last(), times.first()) {
+        if previous_times.len().is_multiple_of(2) {
+            let rest = day.date.and_time(*first_entry) - previous.and_time(*last_exit);
+            if rest.num_minutes() < MIN_REST_MINUTES {
+                violations.push(Violation::ShortRest);
+            }
+        }
+    }
+
+    violations
+}
+
+pub fn period_report(history: &PunchHistory, label: String, days: &[DaySummary]) -> PeriodReport {
+    let worked_days: Vec<&DaySummary> = days.iter().filter(|day| !day.punches.is_empty()).collect();
+
+    let mut starts = Vec::new();
+    let mut ends = Vec::new();
+    let mut lunches = Vec::new();
+    for day in &worked_days {
+        let times = history.times(day.date);
+        starts.extend(times.first().copied());
+        if times.len().is_multiple_of(2) {
+            ends.extend(times.last().copied());
+        }
+        if let (Some(out), Some(back)) = (times.get(1), times.get(2)) {
+            lunches.push((*back - *out).num_minutes());
+        }
+    }
+
+    PeriodReport {
+        label,
+        start_date: days.first().map(|day| day.date).unwrap_or_default(),
+        end_date: days.last().map(|day| day.date).unwrap_or_default(),
+        worked_minutes: days.iter().map(|day| day.worked_minutes).sum(),
+        target_minutes: days.iter().map(|day| day.target_minutes).sum(),
+        balance_minutes: days.iter().map(|day| day.balance_minutes).sum(),
+        overtime_minutes: days.iter().map(|day| day.overtime_minutes).sum(),
+        days_worked: worked_days.len(),
+        average_start: average_time(&starts),
+        average_end: average_time(&ends),
+        average_lunch_minutes: (!lunches.is_empty()).then(|| lunches.iter().sum::<i64>() / lunches.len() as i64),
+        violation_days: worked_days
+            .iter()
+            .map(|day| DayViolations { date: day.date, violations: day_violations(history, day) })
+            .filter(|day| !day.violations.is_empty())
+            .collect(),
+    }
+}
+
+/// Agregados por semana (segunda a domingo) ou por mês do período pedido,
+/// calculados a partir do histórico local de marcações.
+#[tauri::command]
+pub async fn get_work_report(
+    app: AppHandle,
+    period: ReportPeriod,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+) -> Result<Vec<PeriodReport>, String> {
+    if end_date < start_date {
+        return Err("A data final deve ser posterior à data inicial".to_string());
+    }
+
+    let history = history::load_history(&app)?;
+    let schedule = schedule::load_schedule(&app)?;
+    let timesheet = timesheet::build_timesheet(&history, &schedule, start_date, end_date);
+
+    let mut reports = Vec::new();
+    for group in timesheet.days.chunk_by(|a, b| match period {
+        ReportPeriod::Week => a.date.iso_week() == b.date.iso_week(),
+        ReportPeriod::Month => a.date.year() == b.date.year() && a.date.month() == b.date.month(),
+    }) {
+        let first = group[0].date;
+        let label = match period {
+            ReportPeriod::Week => {
+                let monday = first - Duration::days(first.weekday().num_days_from_monday() as i64);
+                format!("Semana de {}", monday.format("%d/%m/%Y"))
+            }
+            ReportPeriod::Month => first.format("%m/%Y").to_string(),
+        };
+        reports.push(period_report(&history, label, group));
+    }
+
+    Ok(reports)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::PunchSource;
+    use crate::schedule::WorkSchedule;
+
+    /// Marcações por dia, com o dia como deslocamento a partir de `monday()`.
+    type Days<'a> = &'a [(i64, &'a [&'a str])];
+    type Expected<'a> = &'a [(i64, &'a [Violation])];
+
+    /// Segunda-feira da semana usada nos casos.
+    fn monday() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 3, 10).unwrap()
+    }
+
+    fn history(days: Days) -> PunchHistory {
+        let mut history = PunchHistory::default();
+        for (offset, times) in days {
+            let times: Vec<NaiveTime> = times
+                .iter()
+                .map(|time| NaiveTime::parse_from_str(time, "%H:%M").unwrap())
+                .collect();
+            history.set_day(monday() + Duration::days(*offset), &times, PunchSource::Manual);
+        }
+        history
+    }
+
+    fn week_report(history: &PunchHistory) -> PeriodReport {
+        let timesheet = timesheet::build_timesheet(history, &WorkSchedule::default(), monday(), monday() + Duration::days(6));
+        period_report(history, "Semana".to_string(), &timesheet.days)
+    }
+
+    #[test]
+    fn detects_clt_violations() {
+        use Violation::*;
+
+        let regular: &[&str] = &["08:00", "12:00", "13:00", "17:00"];
+        let cases: &[(&str, Days, Expected)] = &[
+            ("jornada regular", &[(0, regular), (1, regular)], &[]),
+            ("intervalo de 30 minutos", &[(0, &["08:00", "12:00", "12:30", "17:00"])], &[(0, &[ShortLunch])]),
+            ("intervalo de exatamente 1h", &[(0, regular)], &[]),
+            ("6h sem intervalo", &[(0, &["08:00", "14:00"])], &[]),
+            ("mais de 6h sem intervalo", &[(0, &["08:00", "15:00"])], &[(0, &[ShortLunch])]),
+            ("exatamente 10h", &[(0, &["07:00", "12:00", "13:00", "18:00"])], &[]),
+            ("acima de 10h", &[(0, &["07:00", "12:00", "13:00", "18:01"])], &[(0, &[LongDay])]),
+            ("marcação ímpar", &[(0, &["08:00", "12:00", "13:00"])], &[(0, &[IncompletePunches])]),
+            (
+                "interjornada de 9h",
+                &[(0, &["14:00", "18:00", "19:00", "23:00"]), (1, regular)],
+                &[(1, &[ShortRest])],
+            ),
+            (
+                "interjornada de exatamente 11h",
+                &[(0, &["13:00", "17:00", "18:00", "21:00"]), (1, regular)],
+                &[],
+            ),
+            (
+                "dia anterior sem a saída não conta para a interjornada",
+                &[(0, &["14:00", "18:00", "19:00"]), (1, regular)],
+                &[(0, &[IncompletePunches])],
+            ),
+            (
+                "interjornada com o domingo anterior ao período",
+                &[(-1, &["14:00", "18:00", "19:00", "23:30"]), (0, regular)],
+                &[(0, &[ShortRest])],
+            ),
+            (
+                "violações acumuladas em dois dias seguidos",
+                &[(2, &["12:00", "16:00", "17:00", "22:00"]), (3, &["07:00", "11:00", "11:30", "19:00"])],
+                &[(3, &[ShortLunch, LongDay, ShortRest])],
+            ),
+        ];
+
+        for (name, days, expected) in cases {
+            let report = week_report(&history(days));
+            let found: Vec<(i64, Vec<Violation>)> = report
+                .violation_days
+                .iter()
+                .map(|day| ((day.date - monday()).num_days(), day.violations.clone()))
+                .collect();
+            let expected: Vec<(i64, Vec<Violation>)> = expected
+                .iter()
+                .map(|(offset, violations)| (*offset, violations.to_vec()))
+                .collect();
+            assert_eq!(found, expected, "{}", name);
+        }
+    }
+
+    #[test]
+    fn averages_worked_days() {
+        let history = history(&[
+            (0, &["08:00", "12:00", "13:00", "17:00"]),
+            (1, &["09:00", "12:00", "13:30", "18:00"]),
+            (2, &["08:30", "12:00", "13:00"]),
+        ]);
+
+        let report = week_report(&history);
+        assert_eq!(report.days_worked, 3);
+        assert_eq!(report.average_start.as_deref(), Some("08:30"));
+        // O dia sem a saída não entra na média de saída
+        assert_eq!(report.average_end.as_deref(), Some("17:30"));
+        assert_eq!(report.average_lunch_minutes, Some(70));
+        assert_eq!(report.worked_minutes, 8 * 60 + 7 * 60 + 30 + 3 * 60 + 30);
+    }
+}
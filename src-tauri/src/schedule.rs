@@ -0,0 +1,499 @@
+#[cfg(feature = "app")]
+use chrono::Local;
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+#[cfg(feature = "app")]
+use reqwest::{Client, Method};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "app")]
+use tauri::AppHandle;
+#[cfg(feature = "app")]
+use tauri_plugin_store::StoreExt;
+
+use crate::history;
+use crate::holidays::{self, HolidayCalendar};
+#[cfg(feature = "app")]
+use crate::ics;
+use crate::ics::ParsedEvent;
+use crate::storage::KeyValueStore;
+use crate::timesheet::{self, format_minutes, DAILY_TARGET_MINUTES};
+
+const EVENTS_KEY: &str = "calendar_events";
+/// Antecedência do lembrete para sair para um compromisso.
+const APPOINTMENT_REMINDER_MINUTES: i64 = 15;
+const MEETING_REMINDER_MINUTES: i64 = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EventKind {
+    /// Feriado ou folga: o dia não tem meta.
+    DayOff,
+    /// Consulta médica e outras ausências: a duração é abonada da meta do dia.
+    Appointment,
+    /// Reunião: apenas gera lembrete.
+    Meeting,
+    Other,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlannedEvent {
+    pub uid: String,
+    pub summary: String,
+    pub kind: EventKind,
+    pub start: NaiveDateTime,
+    pub end: NaiveDateTime,
+    pub all_day: bool,
+    /// Arquivo ou URL de onde o evento foi importado.
+    pub source: String,
+}
+
+impl PlannedEvent {
+    fn covers(&self, date: NaiveDate) -> bool {
+        let day_start = date.and_time(NaiveTime::MIN);
+        self.start < day_start + chrono::Duration::days(1) && self.end > day_start
+    }
+
+    fn duration_minutes(&self) -> i64 {
+        (self.end - self.start).num_minutes().max(0)
+    }
+}
+
+fn classify(event: &ParsedEvent) -> EventKind {
+    let text = format!("{} {}", event.summary, event.categories.join(" ")).to_lowercase();
+    let has_any = |words: &[&str]| words.iter().any(|word| text.contains(word));
+
+    if has_any(&["feriado", "holiday", "folga", "férias", "ferias", "day off", "recesso"]) {
+        EventKind::DayOff
+    } else if has_any(&["médic", "medic", "consulta", "exame", "dentista", "hospital", "appointment", "doctor", "ausência", "ausencia"]) {
+        EventKind::Appointment
+    } else if event.all_day {
+        EventKind::Other
+    } else {
+        EventKind::Meeting
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Reminder {
+    pub at: NaiveDateTime,
+    pub title: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DayPlan {
+    pub date: NaiveDate,
+    pub target_minutes: i64,
+    /// Minutos abonados por compromissos marcados no calendário.
+    pub adjustment_minutes: i64,
+    /// Motivo quando o dia não tem meta (fim de semana, feriado, folga).
+    pub day_off: Option<String>,
+    pub events: Vec<PlannedEvent>,
+    pub reminders: Vec<Reminder>,
+}
+
+impl DayPlan {
+    /// Meta usada pelo monitoramento. Em dias sem meta quem trabalha ainda
+    /// recebe os avisos de uma jornada normal.
+    pub fn monitor_target_minutes(&self) -> i64 {
+        if self.day_off.is_some() {
+            (DAILY_TARGET_MINUTES - self.adjustment_minutes).max(0)
+        } else {
+            self.target_minutes
+        }
+    }
+}
+
+/// Metas de cada dia considerando a jornada padrão e os eventos importados do calendário.
+#[derive(Debug, Clone, Default)]
+pub struct WorkSchedule {
+    events: Vec<PlannedEvent>,
+    holidays: HolidayCalendar,
+}
+
+impl WorkSchedule {
+    fn events_on(&self, date: NaiveDate) -> impl Iterator<Item = &PlannedEvent> {
+        self.events.iter().filter(move |event| event.covers(date))
+    }
+
+    pub fn target_minutes(&self, date: NaiveDate) -> i64 {
+        self.day_plan(date, &[]).target_minutes
+    }
+
+    /// Adicional de horas extras do dia (50% ou 100%).
+    pub fn overtime_rate(&self, date: NaiveDate) -> u32 {
+        self.holidays.overtime_rate(date)
+    }
+
+    /// Meta, ausências e lembretes do dia. `punches` são as marcações já feitas,
+    /// usadas para calcular quanto terá sido trabalhado ao sair para um compromisso.
+    pub fn day_plan(&self, date: NaiveDate, punches: &[NaiveTime]) -> DayPlan {
+        let base_target = timesheet::target_minutes(date);
+        let events: Vec<PlannedEvent> = self.events_on(date).cloned().collect();
+
+        let mut day_off = (base_target == 0).then(|| "Fim de semana".to_string());
+        if let Some(holiday) = self.holidays.holiday_on(date) {
+            day_off = Some(holiday.name);
+        }
+        if let Some(event) = events.iter().find(|event| event.kind == EventKind::DayOff) {
+            day_off = Some(event.summary.clone());
+        }
+
+        let adjustment_minutes: i64 = events
+            .iter()
+            .filter(|event| event.kind == EventKind::Appointment && !event.all_day)
+            .map(PlannedEvent::duration_minutes)
+            .sum();
+
+        let target_minutes = if day_off.is_some() {
+            0
+        } else {
+            (base_target - adjustment_minutes).max(0)
+        };
+
+        let reminders = events
+            .iter()
+            .filter(|event| !event.all_day && event.start.date() == date)
+            .filter_map(|event| reminder_for(event, punches))
+            .collect();
+
+        DayPlan {
+            date,
+            target_minutes,
+            adjustment_minutes,
+            day_off,
+            events,
+            reminders,
+        }
+    }
+}
+
+fn reminder_for(event: &PlannedEvent, punches: &[NaiveTime]) -> Option<Reminder> {
+    let start_time = event.start.time().format("%H:%M");
+
+    match event.kind {
+        EventKind::Appointment => {
+            // Considera que o trabalho segue até a saída para o compromisso
+            let mut worked_punches = punches.to_vec();
+            let still_working = !worked_punches.len().is_multiple_of(2);
+            if still_working && worked_punches.last().is_some_and(|last| *last < event.start.time()) {
+                worked_punches.push(event.start.time());
+            }
+            let worked = history::worked_minutes(&worked_punches);
+
+            Some(Reminder {
+                at: event.start - chrono::Duration::minutes(APPOINTMENT_REMINDER_MINUTES),
+                title: format!("⏰ Compromisso às {}", start_time),
+                message: format!(
+                    "Saia às {} para {} — você terá {} trabalhadas",
+                    start_time,
+                    event.summary,
+                    format_minutes(worked)
+                ),
+            })
+        }
+        EventKind::Meeting => Some(Reminder {
+            at: event.start - chrono::Duration::minutes(MEETING_REMINDER_MINUTES),
+            title: format!("⏰ Reunião às {}", start_time),
+            message: format!("{} começa em {} minutos", event.summary, MEETING_REMINDER_MINUTES),
+        }),
+        EventKind::DayOff | EventKind::Other => None,
+    }
+}
+
+#[cfg(feature = "app")]
+pub fn load_schedule(app: &AppHandle) -> Result<WorkSchedule, String> {
+    let store = app.store("noponto.dat")
+        .map_err(|e| format!("Failed to get store: {}", e))?;
+
+    read_schedule(store.as_ref())
+}
+
+pub fn read_schedule(store: &dyn KeyValueStore) -> Result<WorkSchedule, String> {
+    let events = match store.get(EVENTS_KEY) {
+        Some(value) => serde_json::from_value(value)
+            .map_err(|e| format!("Failed to parse calendar events: {}", e))?,
+        None => Vec::new(),
+    };
+
+    Ok(WorkSchedule {
+        events,
+        holidays: HolidayCalendar::new(holidays::read_holiday_config(store)?),
+    })
+}
+
+#[cfg(feature = "app")]
+/// Substitui os eventos importados anteriormente da mesma origem.
+fn save_events(app: &AppHandle, source: &str, parsed: Vec<ParsedEvent>) -> Result<usize, String> {
+    let store = app.store("noponto.dat")
+        .map_err(|e| format!("Failed to get store: {}", e))?;
+
+    let mut schedule = load_schedule(app)?;
+    schedule.events.retain(|event| event.source != source);
+
+    let imported: Vec<PlannedEvent> = parsed
+        .into_iter()
+        .map(|event| PlannedEvent {
+            kind: classify(&event),
+            uid: event.uid,
+            summary: event.summary,
+            start: event.start,
+            end: event.end,
+            all_day: event.all_day,
+            source: source.to_string(),
+        })
+        .filter(|event| event.kind != EventKind::Other)
+        .collect();
+    let count = imported.len();
+    schedule.events.extend(imported);
+
+    let value = serde_json::to_value(&schedule.events)
+        .map_err(|e| format!("Failed to serialize calendar events: {}", e))?;
+    store.set(EVENTS_KEY, value);
+    store.save()
+        .map_err(|e| format!("Failed to save store: {}", e))?;
+
+    println!("Imported {} calendar events from {}", count, source);
+    Ok(count)
+}
+
+/// Extrai o conteúdo dos elementos `calendar-data` de uma resposta multistatus do CalDAV.
+fn extract_calendar_data(xml: &str) -> Vec<String> {
+    let mut calendars = Vec::new();
+    let mut rest = xml;
+
+    while let Some(position) = rest.find("calendar-data") {
+        let after_tag = &rest[position..];
+        let Some(open_end) = after_tag.find('>') else { break };
+        if after_tag[..open_end].ends_with('/') {
+            rest = &after_tag[open_end..];
+            continue;
+        }
+
+        let content = &after_tag[open_end + 1..];
+        let Some(close) = content.find("</") else { break };
+        let data = content[..close].trim();
+        let data = data
+            .strip_prefix("<![CDATA[")
+            .and_then(|data| data.strip_suffix("]]>"))
+            .map(str::to_string)
+            .unwrap_or_else(|| {
+                data.replace("&lt;", "<")
+                    .replace("&gt;", ">")
+                    .replace("&quot;", "\"")
+                    .replace("&apos;", "'")
+                    .replace("&#13;", "\r")
+                    .replace("&amp;", "&")
+            });
+        if !data.is_empty() {
+            calendars.push(data);
+        }
+
+        rest = &content[close..];
+        // Pula o fechamento deste elemento
+        if let Some(next) = rest.find('>') {
+            rest = &rest[next..];
+        }
+    }
+
+    calendars
+}
+
+#[cfg(feature = "app")]
+#[tauri::command]
+pub async fn import_calendar_file(app: AppHandle, path: String) -> Result<usize, String> {
+    let content = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {}", path, e))?;
+
+    save_events(&app, &path, ics::parse_calendar(&content))
+}
+
+#[cfg(feature = "app")]
+/// Busca os eventos de um calendário CalDAV entre uma semana atrás e os próximos 60 dias.
+#[tauri::command]
+pub async fn import_caldav_calendar(
+    app: AppHandle,
+    url: String,
+    username: Option<String>,
+    password: Option<String>,
+) -> Result<usize, String> {
+    let today = Local::now().date_naive();
+    let range_start = today - chrono::Duration::days(7);
+    let range_end = today + chrono::Duration::days(60);
+
+    let body = format!(
+        r#"<?xml version="1.0" encoding="utf-8" ?>
+<C:calendar-query xmlns:D="DAV:" xmlns:C="urn:ietf:params:xml:ns:caldav">
+  <D:prop><C:calendar-data/></D:prop>
+  <C:filter>
+    <C:comp-filter name="VCALENDAR">
+      <C:comp-filter name="VEVENT">
+        <C:time-range start="{}T000000Z" end="{}T000000Z"/>
+      </C:comp-filter>
+    </C:comp-filter>
+  </C:filter>
+</C:calendar-query>"#,
+        range_start.format("%Y%m%d"),
+        range_end.format("%Y%m%d")
+    );
+
+    let method = Method::from_bytes(b"REPORT").map_err(|e| format!("Invalid method: {}", e))?;
+    let mut request = Client::new()
+        .request(method, &url)
+        .header("Depth", "1")
+        .header("Content-Type", "application/xml; charset=utf-8")
+        .body(body);
+    if let Some(username) = username.filter(|username| !username.is_empty()) {
+        request = request.basic_auth(username, password);
+    }
+
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {}", e))?;
+
+    let status = response.status();
+    let response_text = response.text().await
+        .map_err(|e| format!("Failed to read response: {}", e))?;
+
+    if !status.is_success() {
+        return Err(format!("CalDAV request failed with status {}: {}", status, response_text));
+    }
+
+    let events = extract_calendar_data(&response_text)
+        .iter()
+        .flat_map(|calendar| ics::parse_calendar(calendar))
+        .collect();
+
+    save_events(&app, &url, events)
+}
+
+#[cfg(feature = "app")]
+#[tauri::command]
+pub async fn get_day_plan(app: AppHandle, date: Option<NaiveDate>) -> Result<DayPlan, String> {
+    let date = date.unwrap_or_else(|| Local::now().date_naive());
+    let schedule = load_schedule(&app)?;
+    let history = history::load_history(&app)?;
+
+    Ok(schedule.day_plan(date, &history.times(date)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(value: &str) -> NaiveTime {
+        NaiveTime::parse_from_str(value, "%H:%M").unwrap()
+    }
+
+    fn event(kind: EventKind, summary: &str, start: &str, end: &str) -> PlannedEvent {
+        let date = NaiveDate::from_ymd_opt(2025, 3, 12).unwrap();
+        PlannedEvent {
+            uid: format!("{}@test", summary),
+            summary: summary.to_string(),
+            kind,
+            start: date.and_time(time(start)),
+            end: date.and_time(time(end)),
+            all_day: false,
+            source: "test.ics".to_string(),
+        }
+    }
+
+    #[test]
+    fn extracts_every_calendar_data_element() {
+        let xml = r#"<?xml version="1.0" encoding="utf-8"?>
+<D:multistatus xmlns:D="DAV:" xmlns:C="urn:ietf:params:xml:ns:caldav">
+  <D:response>
+    <D:href>/calendars/ana/trabalho/consulta.ics</D:href>
+    <D:propstat>
+      <D:prop>
+        <C:calendar-data><![CDATA[BEGIN:VCALENDAR
+BEGIN:VEVENT
+UID:consulta@test
+DTSTART:20250312T150000
+DTEND:20250312T170000
+SUMMARY:Consulta médica
+END:VEVENT
+END:VCALENDAR]]></C:calendar-data>
+      </D:prop>
+      <D:status>HTTP/1.1 200 OK</D:status>
+    </D:propstat>
+  </D:response>
+  <D:response>
+    <D:href>/calendars/ana/trabalho/reuniao.ics</D:href>
+    <D:propstat>
+      <D:prop>
+        <cal:calendar-data xmlns:cal="urn:ietf:params:xml:ns:caldav">BEGIN:VCALENDAR&#13;
+BEGIN:VEVENT&#13;
+UID:reuniao@test&#13;
+DTSTART:20250312T100000&#13;
+DTEND:20250312T110000&#13;
+SUMMARY:Reunião P&amp;D&#13;
+END:VEVENT&#13;
+END:VCALENDAR</cal:calendar-data>
+      </D:prop>
+    </D:propstat>
+  </D:response>
+  <D:response>
+    <D:href>/calendars/ana/trabalho/vazio.ics</D:href>
+    <D:propstat>
+      <D:prop><C:calendar-data/></D:prop>
+      <D:status>HTTP/1.1 404 Not Found</D:status>
+    </D:propstat>
+  </D:response>
+</D:multistatus>"#;
+
+        let calendars = extract_calendar_data(xml);
+        assert_eq!(calendars.len(), 2);
+        assert!(calendars[0].starts_with("BEGIN:VCALENDAR"));
+        assert!(calendars[1].contains("\r\n"));
+
+        let events: Vec<ParsedEvent> = calendars.iter().flat_map(|calendar| crate::ics::parse_calendar(calendar)).collect();
+        let summaries: Vec<&str> = events.iter().map(|event| event.summary.as_str()).collect();
+        assert_eq!(summaries, vec!["Consulta médica", "Reunião P&D"]);
+    }
+
+    #[test]
+    fn appointment_reminder_counts_work_until_leaving() {
+        let appointment = event(EventKind::Appointment, "Consulta médica", "15:00", "17:00");
+        let punches = [time("07:40"), time("12:00"), time("13:00")];
+
+        let reminder = reminder_for(&appointment, &punches).unwrap();
+        assert_eq!(reminder.at, appointment.start - chrono::Duration::minutes(APPOINTMENT_REMINDER_MINUTES));
+        assert_eq!(reminder.title, "⏰ Compromisso às 15:00");
+        assert_eq!(reminder.message, "Saia às 15:00 para Consulta médica — você terá 6h20 trabalhadas");
+    }
+
+    #[test]
+    fn day_plan_discounts_appointments_and_keeps_reminders() {
+        let schedule = WorkSchedule {
+            events: vec![
+                event(EventKind::Appointment, "Consulta médica", "15:00", "17:00"),
+                event(EventKind::Meeting, "Daily", "09:30", "09:45"),
+            ],
+            holidays: HolidayCalendar::default(),
+        };
+        let date = NaiveDate::from_ymd_opt(2025, 3, 12).unwrap();
+
+        let plan = schedule.day_plan(date, &[time("07:40"), time("12:00"), time("13:00")]);
+        assert_eq!(plan.day_off, None);
+        assert_eq!(plan.adjustment_minutes, 120);
+        assert_eq!(plan.target_minutes, DAILY_TARGET_MINUTES - 120);
+        assert_eq!(plan.reminders.len(), 2);
+        assert!(plan.reminders.iter().any(|reminder| reminder.message.contains("você terá 6h20 trabalhadas")));
+        assert!(plan.reminders.iter().any(|reminder| reminder.message == "Daily começa em 5 minutos"));
+    }
+
+    #[test]
+    fn day_off_events_clear_the_target() {
+        let mut day_off = event(EventKind::DayOff, "Folga", "00:00", "00:00");
+        day_off.all_day = true;
+        day_off.end += chrono::Duration::days(1);
+        let schedule = WorkSchedule { events: vec![day_off], holidays: HolidayCalendar::default() };
+
+        let plan = schedule.day_plan(NaiveDate::from_ymd_opt(2025, 3, 12).unwrap(), &[]);
+        assert_eq!(plan.day_off.as_deref(), Some("Folga"));
+        assert_eq!(plan.target_minutes, 0);
+        assert_eq!(plan.monitor_target_minutes(), DAILY_TARGET_MINUTES);
+        assert!(plan.reminders.is_empty());
+    }
+}
@@ -0,0 +1,157 @@
+//! Status da jornada calculado uma única vez a cada atualização do
+//! monitoramento. Interface, bandeja, MQTT e a CLI exibem este status em vez de
+//! refazer as contas a partir do tempo restante.
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::schedule::Reminder;
+use crate::timesheet::{format_minutes, format_remaining};
+use crate::WARNING_MINUTES;
+
+/// Minutos após a saída prevista ainda tratados como jornada completa, e não
+/// hora extra (variação de até 10 minutos diários, CLT art. 58 §1º).
+pub const COMPLETE_TOLERANCE_MINUTES: i64 = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WorkPhase {
+    Working,
+    /// Últimos minutos antes da saída prevista.
+    FinalMinutes,
+    Complete,
+    Overtime,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NextReminder {
+    /// Horário no formato `HH:MM`.
+    pub at: String,
+    pub title: String,
+}
+
+/// Status completo enviado à interface em `work_status_updated`. O backend é a
+/// única fonte do status; o frontend apenas exibe.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatusSnapshot {
+    pub worked_minutes: i64,
+    pub remaining_minutes: i64,
+    pub overtime_minutes: i64,
+    pub target_minutes: i64,
+    pub expected_end: String,
+    pub phase: WorkPhase,
+    pub is_complete: bool,
+    pub next_reminder: Option<NextReminder>,
+    /// Fim do silêncio das notificações (`HH:MM`), quando silenciadas.
+    pub snoozed_until: Option<String>,
+}
+
+impl StatusSnapshot {
+    /// `remaining_minutes` fica negativo depois da saída prevista.
+    pub fn new(
+        worked_minutes: i64,
+        remaining_minutes: i64,
+        target_minutes: i64,
+        end_time: DateTime<Local>,
+        next_reminder: Option<&Reminder>,
+        snoozed_until: Option<DateTime<Local>>,
+    ) -> Self {
+        let phase = if remaining_minutes > WARNING_MINUTES {
+            WorkPhase::Working
+        } else if remaining_minutes > 0 {
+            WorkPhase::FinalMinutes
+        } else if remaining_minutes >= -COMPLETE_TOLERANCE_MINUTES {
+            WorkPhase::Complete
+        } else {
+            WorkPhase::Overtime
+        };
+
+        StatusSnapshot {
+            worked_minutes,
+            remaining_minutes: remaining_minutes.max(0),
+            overtime_minutes: (-remaining_minutes).max(0),
+            target_minutes,
+            expected_end: end_time.format("%H:%M").to_string(),
+            phase,
+            is_complete: remaining_minutes <= 0,
+            next_reminder: next_reminder.map(|reminder| NextReminder {
+                at: reminder.at.format("%H:%M").to_string(),
+                title: reminder.title.clone(),
+            }),
+            snoozed_until: format_snooze(snoozed_until),
+        }
+    }
+
+    /// Fração trabalhada da meta (0.0 a 1.0); não avança durante o almoço.
+    pub fn progress(&self) -> f64 {
+        if self.target_minutes <= 0 {
+            return 1.0;
+        }
+        (self.worked_minutes as f64 / self.target_minutes as f64).clamp(0.0, 1.0)
+    }
+
+    /// Versão curta: tempo restante, ou `+` e a hora extra.
+    pub fn remaining_label(&self) -> String {
+        match self.phase {
+            WorkPhase::Overtime => format_remaining(-self.overtime_minutes),
+            _ => format_remaining(self.remaining_minutes),
+        }
+    }
+
+    /// Texto completo exibido no tooltip da bandeja e na CLI.
+    pub fn summary(&self) -> String {
+        match self.phase {
+            WorkPhase::Working | WorkPhase::FinalMinutes => {
+                format!("Faltam {} — saída {}", format_minutes(self.remaining_minutes), self.expected_end)
+            }
+            WorkPhase::Complete => format!("Jornada completa — saída {}", self.expected_end),
+            WorkPhase::Overtime => {
+                format!("Hora extra +{} — saída {}", format_minutes(self.overtime_minutes), self.expected_end)
+            }
+        }
+    }
+}
+
+pub fn format_snooze(until: Option<DateTime<Local>>) -> Option<String> {
+    until.map(|until| until.format("%H:%M").to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(worked: i64, remaining: i64) -> StatusSnapshot {
+        let end_time = Local::now() + chrono::Duration::minutes(remaining);
+        StatusSnapshot::new(worked, remaining, 480, end_time, None, None)
+    }
+
+    #[test]
+    fn phase_follows_remaining_minutes_and_tolerance() {
+        assert_eq!(snapshot(400, 80).phase, WorkPhase::Working);
+        assert_eq!(snapshot(477, WARNING_MINUTES).phase, WorkPhase::FinalMinutes);
+        assert_eq!(snapshot(480, 0).phase, WorkPhase::Complete);
+        assert_eq!(snapshot(490, -COMPLETE_TOLERANCE_MINUTES).phase, WorkPhase::Complete);
+        assert_eq!(snapshot(491, -COMPLETE_TOLERANCE_MINUTES - 1).phase, WorkPhase::Overtime);
+    }
+
+    #[test]
+    fn progress_uses_worked_minutes() {
+        // No almoço o tempo restante cai, mas o trabalhado não
+        let lunch = snapshot(240, 200);
+        assert_eq!(lunch.progress(), 0.5);
+        assert_eq!(snapshot(600, -120).progress(), 1.0);
+    }
+
+    #[test]
+    fn labels_match_phase() {
+        let complete = snapshot(485, -5);
+        assert!(complete.summary().starts_with("Jornada completa"));
+        assert_eq!(complete.overtime_minutes, 5);
+
+        let overtime = snapshot(545, -65);
+        assert_eq!(overtime.remaining_label(), "+1h05");
+        assert!(overtime.summary().starts_with("Hora extra +1h05"));
+
+        assert!(snapshot(400, 80).summary().starts_with("Faltam 1h20"));
+    }
+}
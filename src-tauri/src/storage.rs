@@ -0,0 +1,85 @@
+//! Acesso ao `noponto.dat` com ou sem o app rodando. O app usa o
+//! tauri-plugin-store; a CLI lê e grava o mesmo arquivo diretamente.
+
+use serde_json::{Map, Value};
+use std::path::PathBuf;
+use std::sync::Mutex;
+#[cfg(feature = "app")]
+use tauri::Runtime;
+#[cfg(feature = "app")]
+use tauri_plugin_store::Store;
+
+pub const STORE_FILE: &str = "noponto.dat";
+/// Identificador do app (`tauri.conf.json`), nome da pasta de dados.
+pub const APP_IDENTIFIER: &str = "NoPonto";
+
+pub trait KeyValueStore {
+    fn get(&self, key: &str) -> Option<Value>;
+    fn set(&self, key: &str, value: Value);
+    fn save(&self) -> Result<(), String>;
+}
+
+#[cfg(feature = "app")]
+impl<R: Runtime> KeyValueStore for Store<R> {
+    fn get(&self, key: &str) -> Option<Value> {
+        Store::get(self, key)
+    }
+
+    fn set(&self, key: &str, value: Value) {
+        Store::set(self, key, value)
+    }
+
+    fn save(&self) -> Result<(), String> {
+        Store::save(self).map_err(|e| format!("Failed to save store: {}", e))
+    }
+}
+
+/// Pasta de dados do app, a mesma usada pelo tauri-plugin-store.
+pub fn app_data_dir() -> Result<PathBuf, String> {
+    dirs::data_dir()
+        .map(|dir| dir.join(APP_IDENTIFIER))
+        .ok_or_else(|| "Não foi possível localizar a pasta de dados do usuário".to_string())
+}
+
+/// O `noponto.dat` lido direto do disco, no formato JSON do tauri-plugin-store.
+pub struct FileStore {
+    path: PathBuf,
+    entries: Mutex<Map<String, Value>>,
+}
+
+impl FileStore {
+    pub fn open() -> Result<Self, String> {
+        let path = app_data_dir()?.join(STORE_FILE);
+
+        let entries = match std::fs::read(&path) {
+            Ok(bytes) => serde_json::from_slice(&bytes)
+                .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Map::new(),
+            Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+        };
+
+        Ok(FileStore { path, entries: Mutex::new(entries) })
+    }
+}
+
+impl KeyValueStore for FileStore {
+    fn get(&self, key: &str) -> Option<Value> {
+        self.entries.lock().unwrap().get(key).cloned()
+    }
+
+    fn set(&self, key: &str, value: Value) {
+        self.entries.lock().unwrap().insert(key.to_string(), value);
+    }
+
+    fn save(&self) -> Result<(), String> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+
+        let json = serde_json::to_vec_pretty(&*self.entries.lock().unwrap())
+            .map_err(|e| format!("Failed to serialize store: {}", e))?;
+        std::fs::write(&self.path, json)
+            .map_err(|e| format!("Failed to write {}: {}", self.path.display(), e))
+    }
+}
@@ -0,0 +1,140 @@
+use chrono::{Datelike, NaiveDate, Weekday};
+use serde::Serialize;
+
+use crate::history::{self, PunchHistory};
+use crate::schedule::WorkSchedule;
+
+/// Jornada diária padrão (8 horas).
+pub const DAILY_TARGET_MINUTES: i64 = 8 * 60;
+
+/// Meta de minutos trabalhados no dia: jornada padrão de segunda a sexta.
+/// Ajustes de calendário são aplicados por `WorkSchedule`.
+pub fn target_minutes(date: NaiveDate) -> i64 {
+    match date.weekday() {
+        Weekday::Sat | Weekday::Sun => 0,
+        _ => DAILY_TARGET_MINUTES,
+    }
+}
+
+/// Formata minutos como `7h05`, com sinal quando negativo.
+pub fn format_minutes(minutes: i64) -> String {
+    let sign = if minutes < 0 { "-" } else { "" };
+    let minutes = minutes.abs();
+    format!("{}{}h{:02}", sign, minutes / 60, minutes % 60)
+}
+
+/// Tempo restante até a saída, inclusive `0h00` na hora exata; depois dela, a
+/// hora extra com `+` (`+0h25`).
+pub fn format_remaining(remaining_minutes: i64) -> String {
+    if remaining_minutes >= 0 {
+        format_minutes(remaining_minutes)
+    } else {
+        format!("+{}", format_minutes(-remaining_minutes))
+    }
+}
+
+pub fn weekday_name(date: NaiveDate) -> &'static str {
+    match date.weekday() {
+        Weekday::Mon => "Seg",
+        Weekday::Tue => "Ter",
+        Weekday::Wed => "Qua",
+        Weekday::Thu => "Qui",
+        Weekday::Fri => "Sex",
+        Weekday::Sat => "Sáb",
+        Weekday::Sun => "Dom",
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DaySummary {
+    pub date: NaiveDate,
+    pub punches: Vec<String>,
+    pub worked_minutes: i64,
+    pub target_minutes: i64,
+    pub overtime_minutes: i64,
+    /// Adicional das horas extras do dia: 100% em domingos e feriados, 50% nos demais.
+    pub overtime_rate: u32,
+    pub balance_minutes: i64,
+    /// Saldo acumulado do banco de horas até este dia, inclusive.
+    pub bank_minutes: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Timesheet {
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub days: Vec<DaySummary>,
+    pub total_worked_minutes: i64,
+    pub total_target_minutes: i64,
+    pub total_overtime_minutes: i64,
+    /// Parte das horas extras feita em domingos e feriados.
+    pub total_overtime_100_minutes: i64,
+    pub balance_minutes: i64,
+}
+
+/// Monta o espelho de ponto de todos os dias do período, inclusive os sem marcações.
+pub fn build_timesheet(
+    history: &PunchHistory,
+    schedule: &WorkSchedule,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+) -> Timesheet {
+    let mut days = Vec::new();
+    let mut bank_minutes = 0;
+
+    for date in start_date.iter_days().take_while(|date| *date <= end_date) {
+        let times = history.times(date);
+        let worked_minutes = history::worked_minutes(&times);
+        let target_minutes = schedule.target_minutes(date);
+        let balance_minutes = worked_minutes - target_minutes;
+        bank_minutes += balance_minutes;
+
+        days.push(DaySummary {
+            date,
+            punches: times.iter().map(|time| time.format("%H:%M").to_string()).collect(),
+            worked_minutes,
+            target_minutes,
+            overtime_minutes: balance_minutes.max(0),
+            overtime_rate: schedule.overtime_rate(date),
+            balance_minutes,
+            bank_minutes,
+        });
+    }
+
+    Timesheet {
+        start_date,
+        end_date,
+        total_worked_minutes: days.iter().map(|day| day.worked_minutes).sum(),
+        total_target_minutes: days.iter().map(|day| day.target_minutes).sum(),
+        total_overtime_minutes: days.iter().map(|day| day.overtime_minutes).sum(),
+        total_overtime_100_minutes: days
+            .iter()
+            .filter(|day| day.overtime_rate == 100)
+            .map(|day| day.overtime_minutes)
+            .sum(),
+        balance_minutes: bank_minutes,
+        days,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_minutes_with_sign() {
+        assert_eq!(format_minutes(0), "0h00");
+        assert_eq!(format_minutes(425), "7h05");
+        assert_eq!(format_minutes(-90), "-1h30");
+    }
+
+    #[test]
+    fn formats_remaining_and_overtime() {
+        assert_eq!(format_remaining(95), "1h35");
+        assert_eq!(format_remaining(1), "0h01");
+        assert_eq!(format_remaining(0), "0h00");
+        assert_eq!(format_remaining(-1), "+0h01");
+        assert_eq!(format_remaining(-25), "+0h25");
+        assert_eq!(format_remaining(-125), "+2h05");
+    }
+}
@@ -0,0 +1,297 @@
+use chrono::{DateTime, Local};
+use tauri::{
+    image::Image,
+    menu::{Menu, MenuItem, PredefinedMenuItem},
+    tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
+    AppHandle, Emitter, Manager, Wry,
+};
+
+use crate::events::{self, WorkEvent};
+use crate::status::{StatusSnapshot, WorkPhase};
+use crate::{MonitorControl, SharedState};
+
+pub const TRAY_ID: &str = "main";
+pub const DEFAULT_TOOLTIP: &str = "NoPonto - Controle de Ponto";
+/// Tempo em que as notificações ficam silenciadas pelo menu da bandeja.
+const SNOOZE_MINUTES: i64 = 30;
+
+const BASE_ICON: &[u8] = include_bytes!("../icons/64x64.png");
+/// Espessura do anel de progresso, em pixels do ícone de 64x64.
+const RING_WIDTH: f64 = 7.0;
+
+const TRACK_COLOR: [u8; 4] = [128, 128, 128, 110];
+const PROGRESS_COLOR: [u8; 4] = [76, 175, 80, 255];
+const WARNING_COLOR: [u8; 4] = [255, 179, 0, 255];
+const COMPLETE_COLOR: [u8; 4] = [33, 150, 243, 255];
+
+fn blend(pixel: &mut [u8], color: [u8; 4], coverage: f64) {
+    let alpha = color[3] as f64 / 255.0 * coverage.clamp(0.0, 1.0);
+    for channel in 0..3 {
+        pixel[channel] = (color[channel] as f64 * alpha + pixel[channel] as f64 * (1.0 - alpha)).round() as u8;
+    }
+    pixel[3] = (255.0 * alpha + pixel[3] as f64 * (1.0 - alpha)).round() as u8;
+}
+
+/// Desenha o ícone base reduzido dentro de um anel preenchido no sentido
+/// horário a partir do topo, proporcional a `progress` (0.0 a 1.0).
+fn render_progress_icon(progress: f64, color: [u8; 4]) -> Option<Image<'static>> {
+    let base = Image::from_bytes(BASE_ICON).ok()?;
+    let (width, height) = (base.width(), base.height());
+    let size = width.min(height) as f64;
+    let center = size / 2.0;
+    let outer = center;
+    let inner = outer - RING_WIDTH;
+    // Margem de 1px entre o anel e o ícone
+    let scale = size / ((inner - 1.0) * 2.0);
+
+    let mut rgba = vec![0u8; (width * height * 4) as usize];
+    for y in 0..height {
+        for x in 0..width {
+            let dx = x as f64 + 0.5 - center;
+            let dy = y as f64 + 0.5 - center;
+            let distance = (dx * dx + dy * dy).sqrt();
+            let offset = ((y * width + x) * 4) as usize;
+            let pixel = &mut rgba[offset..offset + 4];
+
+            // Ícone base reduzido (vizinho mais próximo) no centro
+            let source_x = (center + dx * scale).floor();
+            let source_y = (center + dy * scale).floor();
+            if source_x >= 0.0 && source_y >= 0.0 && (source_x as u32) < width && (source_y as u32) < height {
+                let source = ((source_y as u32 * width + source_x as u32) * 4) as usize;
+                pixel.copy_from_slice(&base.rgba()[source..source + 4]);
+            }
+
+            // Suaviza as bordas do anel com cobertura parcial
+            let coverage = (outer - distance).min(distance - inner).clamp(-0.5, 0.5) + 0.5;
+            if coverage <= 0.0 {
+                continue;
+            }
+            let angle = dx.atan2(-dy).rem_euclid(std::f64::consts::TAU) / std::f64::consts::TAU;
+            let ring_color = if angle <= progress { color } else { TRACK_COLOR };
+            blend(pixel, ring_color, coverage);
+        }
+    }
+
+    Some(Image::new_owned(rgba, width, height))
+}
+
+/// Atualiza ícone, tooltip e título da bandeja. Chamado a cada minuto pelo monitoramento.
+fn update_status(app: &AppHandle, status: &StatusSnapshot) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else { return };
+
+    let color = match status.phase {
+        WorkPhase::Working => PROGRESS_COLOR,
+        WorkPhase::FinalMinutes => WARNING_COLOR,
+        WorkPhase::Complete | WorkPhase::Overtime => COMPLETE_COLOR,
+    };
+    if let Some(icon) = render_progress_icon(status.progress(), color) {
+        let _ = tray.set_icon(Some(icon));
+    }
+
+    let text = status.summary();
+    if let Some(menu) = app.try_state::<TrayMenu>() {
+        let _ = menu.remaining.set_text(&text);
+    }
+    let _ = tray.set_tooltip(Some(text));
+    // Versão curta exibida ao lado do ícone (macOS e Linux)
+    let _ = tray.set_title(Some(status.remaining_label()));
+}
+
+/// Volta o ícone da bandeja ao estado sem monitoramento.
+fn reset_status(app: &AppHandle) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else { return };
+
+    if let Some(menu) = app.try_state::<TrayMenu>() {
+        let _ = menu.remaining.set_text(IDLE_LABEL);
+    }
+    let _ = tray.set_icon(app.default_window_icon().cloned());
+    let _ = tray.set_tooltip(Some(DEFAULT_TOOLTIP));
+    let _ = tray.set_title(None::<&str>);
+}
+
+/// Alterna o item de iniciar/parar conforme o estado do monitoramento.
+fn set_monitoring(app: &AppHandle, monitoring: bool) {
+    if let Some(menu) = app.try_state::<TrayMenu>() {
+        let label = if monitoring { "Parar monitoramento" } else { "Iniciar monitoramento" };
+        let _ = menu.toggle.set_text(label);
+    }
+}
+
+/// Mantém a bandeja em dia com os eventos do monitoramento.
+pub fn listen(app: &AppHandle) {
+    events::listen(app, |app, event| match event {
+        WorkEvent::MonitoringStarted { .. } => set_monitoring(app, true),
+        WorkEvent::StatusUpdated { status } => update_status(app, &status),
+        WorkEvent::MonitoringStopped => {
+            reset_status(app);
+            set_monitoring(app, false);
+        }
+        _ => {}
+    });
+}
+
+const IDLE_LABEL: &str = "Monitoramento parado";
+
+/// Itens do menu atualizados durante o monitoramento.
+struct TrayMenu {
+    toggle: MenuItem<Wry>,
+    remaining: MenuItem<Wry>,
+    snooze: MenuItem<Wry>,
+}
+
+pub fn show_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+    }
+}
+
+/// Erros das ações do menu aparecem como notificação, já que a janela pode estar oculta.
+async fn notify_result(app: &AppHandle, result: Result<String, String>) {
+    let (title, message) = match result {
+        Ok(message) if message.is_empty() => return,
+        Ok(message) => ("NoPonto".to_string(), message),
+        Err(e) => ("❌ NoPonto".to_string(), e),
+    };
+    let _ = crate::show_system_notification(app.clone(), title, message).await;
+}
+
+/// Inicia o monitoramento com os horários salvos, avisando por notificação se faltar algum.
+pub fn start_monitoring(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let result = crate::start_monitoring_from_store(&app).await.map(|_| String::new());
+        notify_result(&app, result).await;
+    });
+}
+
+fn toggle_monitoring(app: &AppHandle) {
+    let state = app.state::<SharedState>().inner().clone();
+    let monitoring = state.lock().unwrap().is_some();
+
+    if monitoring {
+        crate::end_work_monitoring(app, &state);
+    } else {
+        start_monitoring(app);
+    }
+}
+
+pub fn sync_from_provider(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let result = crate::sync_and_start_monitoring(&app).await;
+        notify_result(&app, result).await;
+    });
+}
+
+fn snooze_label(until: Option<DateTime<Local>>) -> String {
+    match until {
+        Some(until) => format!("Notificações silenciadas até {} (reativar)", until.format("%H:%M")),
+        None => format!("Silenciar notificações por {} min", SNOOZE_MINUTES),
+    }
+}
+
+fn snooze_notifications(app: &AppHandle) {
+    let control = app.state::<MonitorControl>();
+    let mut snoozed_until = control.snoozed_until.lock().unwrap();
+
+    let until = if snoozed_until.is_some_and(|until| Local::now() < until) {
+        None
+    } else {
+        Some(Local::now() + chrono::Duration::minutes(SNOOZE_MINUTES))
+    };
+    *snoozed_until = until;
+
+    drop(snoozed_until);
+    if let Some(menu) = app.try_state::<TrayMenu>() {
+        let _ = menu.snooze.set_text(snooze_label(until));
+    }
+    events::publish(app, WorkEvent::SnoozeChanged { until });
+
+    // Volta o item ao texto padrão quando o silêncio acaba sem ser reativado
+    if let Some(until) = until {
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            let wait = (until - Local::now()).to_std().unwrap_or_default();
+            tokio::time::sleep(wait).await;
+
+            let control = app.state::<MonitorControl>();
+            let mut snoozed_until = control.snoozed_until.lock().unwrap();
+            if *snoozed_until == Some(until) {
+                *snoozed_until = None;
+                drop(snoozed_until);
+                if let Some(menu) = app.try_state::<TrayMenu>() {
+                    let _ = menu.snooze.set_text(snooze_label(None));
+                }
+                events::publish(&app, WorkEvent::SnoozeChanged { until: None });
+            }
+        });
+    }
+}
+
+pub fn create_system_tray(app: &AppHandle) -> tauri::Result<()> {
+    let remaining_i = MenuItem::with_id(app, "remaining", IDLE_LABEL, false, None::<&str>)?;
+    let toggle_i = MenuItem::with_id(app, "toggle_monitoring", "Iniciar monitoramento", true, None::<&str>)?;
+    let sync_i = MenuItem::with_id(app, "sync", "Sincronizar com o sistema de ponto", true, None::<&str>)?;
+    let snooze_i = MenuItem::with_id(app, "snooze", snooze_label(None), true, None::<&str>)?;
+    let settings_i = MenuItem::with_id(app, "settings", "Configurações", true, None::<&str>)?;
+    let show_i = MenuItem::with_id(app, "show", "Mostrar", true, None::<&str>)?;
+    let quit_i = MenuItem::with_id(app, "quit", "Sair", true, None::<&str>)?;
+    let menu = Menu::with_items(app, &[
+        &remaining_i,
+        &PredefinedMenuItem::separator(app)?,
+        &toggle_i,
+        &sync_i,
+        &snooze_i,
+        &PredefinedMenuItem::separator(app)?,
+        &settings_i,
+        &show_i,
+        &quit_i,
+    ])?;
+
+    app.manage(TrayMenu {
+        toggle: toggle_i,
+        remaining: remaining_i,
+        snooze: snooze_i,
+    });
+
+    let _ = TrayIconBuilder::with_id(TRAY_ID)
+        .tooltip(DEFAULT_TOOLTIP)
+        .icon(app.default_window_icon().unwrap().clone())
+        .menu(&menu)
+        .show_menu_on_left_click(false)
+        .on_menu_event(move |app, event| match event.id.as_ref() {
+            "quit" => {
+                println!("Quit menu item was clicked");
+                app.exit(0);
+            }
+            "show" => {
+                println!("Show menu item was clicked");
+                show_main_window(app);
+            }
+            "toggle_monitoring" => toggle_monitoring(app),
+            "sync" => sync_from_provider(app),
+            "snooze" => snooze_notifications(app),
+            "settings" => {
+                show_main_window(app);
+                let _ = app.emit("open_settings", ());
+            }
+            _ => {
+                println!("Unknown menu item clicked: {:?}", event.id);
+            }
+        })
+        .on_tray_icon_event(|tray, event| {
+            if let TrayIconEvent::Click {
+                button: MouseButton::Left,
+                button_state: MouseButtonState::Up,
+                ..
+            } = event
+            {
+                show_main_window(tray.app_handle());
+            }
+        })
+        .build(app)?;
+
+    Ok(())
+}
@@ -0,0 +1,339 @@
+//! Canal de notificação por webhook: cada evento do monitoramento é enviado às
+//! URLs configuradas, com método, cabeçalhos e corpo JSON por evento, novas
+//! tentativas em caso de falha e um registro das últimas entregas.
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+
+use crate::delivery::{self, Delivery};
+use crate::events::{self, WorkEvent};
+use crate::timesheet::format_minutes;
+
+const CONFIG_KEY: &str = "webhooks";
+const DELIVERIES_KEY: &str = "webhook_deliveries";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationEvent {
+    /// Início do monitoramento do dia, com a saída prevista.
+    Start,
+    /// Poucos minutos para completar a jornada.
+    Warning,
+    Complete,
+    /// Cada hora cheia de hora extra depois da jornada completa.
+    Overtime,
+    SyncError,
+}
+
+impl NotificationEvent {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            NotificationEvent::Start => "start",
+            NotificationEvent::Warning => "warning",
+            NotificationEvent::Complete => "complete",
+            NotificationEvent::Overtime => "overtime",
+            NotificationEvent::SyncError => "sync_error",
+        }
+    }
+}
+
+/// Dados de um evento, repassados aos canais de notificação.
+#[derive(Debug, Clone, Serialize)]
+pub struct NotificationPayload {
+    pub event: NotificationEvent,
+    pub title: String,
+    pub message: String,
+    /// Minutos restantes (negativo em hora extra), quando há monitoramento.
+    pub remaining_minutes: Option<i64>,
+    pub end_time: Option<String>,
+    pub timestamp: DateTime<Local>,
+}
+
+impl NotificationPayload {
+    pub fn new(event: NotificationEvent, title: impl Into<String>, message: impl Into<String>) -> Self {
+        NotificationPayload {
+            event,
+            title: title.into(),
+            message: message.into(),
+            remaining_minutes: None,
+            end_time: None,
+            timestamp: Local::now(),
+        }
+    }
+
+    pub fn with_status(mut self, remaining_minutes: i64, end_time: DateTime<Local>) -> Self {
+        self.remaining_minutes = Some(remaining_minutes);
+        self.end_time = Some(end_time.format("%H:%M").to_string());
+        self
+    }
+
+    /// Payload enviado aos canais para o evento do monitoramento. `None` para
+    /// eventos que não vão aos canais: atualizações de status, lembretes e
+    /// repetições do aviso de fim de jornada.
+    pub fn from_event(event: &WorkEvent) -> Option<Self> {
+        let payload = match event {
+            WorkEvent::MonitoringStarted { end_time, announce: true, .. } => NotificationPayload::new(
+                NotificationEvent::Start,
+                "🕗 Início da jornada",
+                format!("Saída prevista às {}", end_time.format("%H:%M")),
+            ).with_status((*end_time - Local::now()).num_minutes(), *end_time),
+            WorkEvent::AlmostComplete { remaining_minutes, end_time, first: true } => NotificationPayload::new(
+                NotificationEvent::Warning,
+                "⏰ Quase Acabando!",
+                format!("Faltam {} minutos para completar a jornada — saída às {}", remaining_minutes, end_time.format("%H:%M")),
+            ).with_status(*remaining_minutes, *end_time),
+            WorkEvent::Complete { end_time } => NotificationPayload::new(
+                NotificationEvent::Complete,
+                "🎉 Jornada Completa!",
+                format!("Jornada completa — saída às {}", end_time.format("%H:%M")),
+            ).with_status((*end_time - Local::now()).num_minutes(), *end_time),
+            WorkEvent::Overtime { overtime_minutes, end_time } => NotificationPayload::new(
+                NotificationEvent::Overtime,
+                "⏱️ Hora extra",
+                format!("Você já fez {} de hora extra hoje", format_minutes(*overtime_minutes)),
+            ).with_status(-overtime_minutes, *end_time),
+            WorkEvent::SyncFailed { error } => NotificationPayload::new(
+                NotificationEvent::SyncError,
+                "⚠️ Falha na sincronização",
+                format!("Não foi possível buscar os horários do sistema de ponto: {}", error),
+            ),
+            _ => return None,
+        };
+        Some(payload)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Webhook {
+    pub name: String,
+    pub url: String,
+    #[serde(default = "default_method")]
+    pub method: String,
+    /// Salvos criptografados com o restante da configuração (podem conter tokens).
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    /// Eventos enviados; vazio envia todos.
+    #[serde(default)]
+    pub events: Vec<NotificationEvent>,
+    /// Corpo JSON por evento, com `{{event}}`, `{{title}}`, `{{message}}`,
+    /// `{{remaining_minutes}}`, `{{end_time}}` e `{{timestamp}}`. Sem modelo,
+    /// o payload é enviado como está.
+    #[serde(default)]
+    pub templates: HashMap<NotificationEvent, String>,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_method() -> String {
+    "POST".to_string()
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookConfig {
+    #[serde(default)]
+    pub webhooks: Vec<Webhook>,
+    /// Tentativas por entrega, com espera dobrando a cada falha (2s, 4s, ...).
+    #[serde(default = "delivery::default_max_attempts")]
+    pub max_attempts: u32,
+}
+
+impl Default for WebhookConfig {
+    fn default() -> Self {
+        WebhookConfig { webhooks: Vec::new(), max_attempts: delivery::default_max_attempts() }
+    }
+}
+
+fn json_escape(value: &str) -> String {
+    let quoted = serde_json::to_string(value).unwrap_or_default();
+    quoted[1..quoted.len() - 1].to_string()
+}
+
+/// Substitui os campos do payload no modelo. Campos de texto são escapados
+/// para uso dentro de aspas; `{{remaining_minutes}}` é numérico (`null` sem
+/// monitoramento).
+pub fn render_template(template: &str, payload: &NotificationPayload) -> Result<Value, String> {
+    let remaining = payload.remaining_minutes.map(|minutes| minutes.to_string()).unwrap_or_else(|| "null".to_string());
+    let rendered = template
+        .replace("{{event}}", payload.event.as_str())
+        .replace("{{title}}", &json_escape(&payload.title))
+        .replace("{{message}}", &json_escape(&payload.message))
+        .replace("{{remaining_minutes}}", &remaining)
+        .replace("{{end_time}}", &json_escape(payload.end_time.as_deref().unwrap_or("")))
+        .replace("{{timestamp}}", &payload.timestamp.to_rfc3339());
+
+    serde_json::from_str(&rendered)
+        .map_err(|e| format!("Invalid JSON body for {}: {}", payload.event.as_str(), e))
+}
+
+fn body_for(webhook: &Webhook, payload: &NotificationPayload) -> Result<Value, String> {
+    match webhook.templates.get(&payload.event) {
+        Some(template) => render_template(template, payload),
+        None => serde_json::to_value(payload)
+            .map_err(|e| format!("Failed to serialize payload: {}", e)),
+    }
+}
+
+pub async fn deliver(webhook: &Webhook, payload: &NotificationPayload, max_attempts: u32) -> Delivery {
+    let request = delivery::Request {
+        name: webhook.name.clone(),
+        method: webhook.method.clone(),
+        url: webhook.url.clone(),
+        headers: webhook.headers.clone(),
+    };
+    delivery::send_with_retries(&request, payload.event, body_for(webhook, payload), max_attempts).await
+}
+
+pub fn load_webhook_config(app: &AppHandle) -> Result<WebhookConfig, String> {
+    let store = app.store("noponto.dat")
+        .map_err(|e| format!("Failed to get store: {}", e))?;
+
+    let Some(encrypted) = store.get(CONFIG_KEY) else { return Ok(WebhookConfig::default()) };
+    let Some(encrypted) = encrypted.as_str() else { return Ok(WebhookConfig::default()) };
+
+    let json = crate::decrypt_data(encrypted)?;
+    serde_json::from_str(&json)
+        .map_err(|e| format!("Failed to parse webhook config: {}", e))
+}
+
+/// Envia o evento, em segundo plano, a todos os webhooks inscritos nele.
+fn dispatch(app: &AppHandle, payload: NotificationPayload) {
+    let config = match load_webhook_config(app) {
+        Ok(config) => config,
+        Err(e) => {
+            println!("Failed to load webhook config: {}", e);
+            return;
+        }
+    };
+
+    let subscribed = config.webhooks.into_iter().filter(|webhook| {
+        webhook.enabled && (webhook.events.is_empty() || webhook.events.contains(&payload.event))
+    });
+
+    for webhook in subscribed {
+        let app = app.clone();
+        let payload = payload.clone();
+        let max_attempts = config.max_attempts;
+        tauri::async_runtime::spawn(async move {
+            let delivery = deliver(&webhook, &payload, max_attempts).await;
+            if !delivery.success {
+                println!("Webhook {} failed: {}", webhook.name, delivery.error.as_deref().unwrap_or(""));
+            }
+            if let Err(e) = delivery::record(&app, DELIVERIES_KEY, delivery) {
+                println!("Failed to record webhook delivery: {}", e);
+            }
+        });
+    }
+}
+
+/// Assina o barramento de eventos e envia os eventos aos webhooks.
+pub fn listen(app: &AppHandle) {
+    events::listen(app, |app, event| {
+        if let Some(payload) = NotificationPayload::from_event(&event) {
+            dispatch(app, payload);
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn get_webhook_config(app: AppHandle) -> Result<WebhookConfig, String> {
+    load_webhook_config(&app)
+}
+
+#[tauri::command]
+pub async fn save_webhook_config(app: AppHandle, config: WebhookConfig) -> Result<(), String> {
+    for webhook in &config.webhooks {
+        reqwest::Url::parse(&webhook.url)
+            .map_err(|e| format!("URL inválida para o webhook {}: {}", webhook.name, e))?;
+    }
+
+    let json = serde_json::to_string(&config)
+        .map_err(|e| format!("Failed to serialize webhook config: {}", e))?;
+    let encrypted = crate::encrypt_data(&json)?;
+
+    let store = app.store("noponto.dat")
+        .map_err(|e| format!("Failed to get store: {}", e))?;
+    store.set(CONFIG_KEY, Value::String(encrypted));
+
+    store.save()
+        .map_err(|e| format!("Failed to save store: {}", e))
+}
+
+#[tauri::command]
+pub async fn get_webhook_deliveries(app: AppHandle) -> Result<Vec<Delivery>, String> {
+    delivery::load_log(&app, DELIVERIES_KEY)
+}
+
+/// Envia um evento de teste, sem novas tentativas, e registra o resultado.
+#[tauri::command]
+pub async fn test_webhook(app: AppHandle, webhook: Webhook, event: Option<NotificationEvent>) -> Result<Delivery, String> {
+    let payload = NotificationPayload::new(
+        event.unwrap_or(NotificationEvent::Complete),
+        "🧪 Teste do NoPonto",
+        "Se você recebeu esta mensagem, o webhook está configurado corretamente.",
+    );
+
+    let delivery = deliver(&webhook, &payload, 1).await;
+    delivery::record(&app, DELIVERIES_KEY, delivery.clone())?;
+    Ok(delivery)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn payload() -> NotificationPayload {
+        let end_time = Local.with_ymd_and_hms(2025, 3, 12, 17, 20, 0).unwrap();
+        NotificationPayload::new(NotificationEvent::Warning, "⏰ Quase \"acabando\"", "Faltam 3 minutos\nsaída às 17:20")
+            .with_status(3, end_time)
+    }
+
+    #[test]
+    fn renders_every_placeholder() {
+        let template = r#"{"event": "{{event}}", "title": "{{title}}", "text": "{{message}}", "left": {{remaining_minutes}}, "end": "{{end_time}}", "at": "{{timestamp}}"}"#;
+        let payload = payload();
+
+        let body = render_template(template, &payload).unwrap();
+        assert_eq!(body["event"], "warning");
+        assert_eq!(body["title"], "⏰ Quase \"acabando\"");
+        assert_eq!(body["text"], "Faltam 3 minutos\nsaída às 17:20");
+        assert_eq!(body["left"], 3);
+        assert_eq!(body["end"], "17:20");
+        assert_eq!(body["at"], payload.timestamp.to_rfc3339());
+    }
+
+    #[test]
+    fn remaining_minutes_is_null_without_status() {
+        let payload = NotificationPayload::new(NotificationEvent::SyncError, "⚠️ Falha", "Erro");
+
+        let body = render_template(r#"{"left": {{remaining_minutes}}, "end": "{{end_time}}"}"#, &payload).unwrap();
+        assert_eq!(body["left"], Value::Null);
+        assert_eq!(body["end"], "");
+    }
+
+    #[test]
+    fn end_time_is_escaped() {
+        let mut payload = payload();
+        payload.end_time = Some("17:20\", \"admin\": true, \"x\": \"".to_string());
+
+        let body = render_template(r#"{"end": "{{end_time}}"}"#, &payload).unwrap();
+        assert_eq!(body.as_object().unwrap().len(), 1);
+        assert_eq!(body["end"], payload.end_time.unwrap());
+    }
+
+    #[test]
+    fn invalid_template_is_an_error() {
+        let error = render_template(r#"{"text": "{{message}}""#, &payload()).unwrap_err();
+        assert!(error.starts_with("Invalid JSON body for warning"));
+
+        assert!(render_template(r#"{"left": "{{remaining_minutes}}}"#, &payload()).is_err());
+    }
+}
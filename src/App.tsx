@@ -18,12 +18,13 @@ import {
   AccordionDetails,
   IconButton
 } from '@mui/material';
-import { Schedule, AccessTime, Work, NotificationsActive, ExpandMore, Settings as SettingsIcon, Download } from '@mui/icons-material';
+import { Schedule, AccessTime, Work, NotificationsActive, ExpandMore, Settings as SettingsIcon, Download, Description } from '@mui/icons-material';
 import { invoke } from '@tauri-apps/api/core';
 import { listen } from '@tauri-apps/api/event';
 import { Store } from '@tauri-apps/plugin-store';
 import { isPermissionGranted, requestPermission } from '@tauri-apps/plugin-notification';
-import { format, differenceInMinutes, parse, addHours } from 'date-fns';
+import { save } from '@tauri-apps/plugin-dialog';
+import { format, startOfMonth, endOfMonth } from 'date-fns';
 import CustomNotification from './components/CustomNotification';
 import Settings from './components/Settings';
 
@@ -40,13 +41,49 @@ interface TimeData {
   inicio2: string;
 }
 
+// Status calculado pelo backend e recebido em `work_status_updated`
 interface WorkStatus {
-  remainingTime: string;
-  endTime: string;
-  isComplete: boolean;
-  totalWorked: string;
+  worked_minutes: number;
+  remaining_minutes: number;
+  overtime_minutes: number;
+  target_minutes: number;
+  expected_end: string;
+  phase: 'working' | 'final_minutes' | 'complete' | 'overtime';
+  is_complete: boolean;
+  next_reminder: { at: string; title: string } | null;
+  snoozed_until: string | null;
 }
 
+const formatMinutes = (minutes: number) => `${Math.floor(minutes / 60)}h ${minutes % 60}m`;
+
+// Cores de cada fase da jornada calculada pelo backend
+const phaseGradients: Record<WorkStatus['phase'], string> = {
+  working: 'linear-gradient(90deg, #3b82f6 0%, #1d4ed8 100%)',
+  final_minutes: 'linear-gradient(90deg, #f59e0b 0%, #d97706 100%)',
+  complete: 'linear-gradient(90deg, #10b981 0%, #059669 100%)',
+  overtime: 'linear-gradient(90deg, #ef4444 0%, #b91c1c 100%)',
+};
+
+const phaseColors: Record<WorkStatus['phase'], string> = {
+  working: '#f59e0b',
+  final_minutes: '#f59e0b',
+  complete: '#10b981',
+  overtime: '#ef4444',
+};
+
+const phaseDescription = (status: WorkStatus) => {
+  switch (status.phase) {
+    case 'working':
+      return `Faltam ${formatMinutes(status.remaining_minutes)} para completar ${formatMinutes(status.target_minutes)}`;
+    case 'final_minutes':
+      return `Últimos minutos! Faltam ${formatMinutes(status.remaining_minutes)} — saída às ${status.expected_end}`;
+    case 'complete':
+      return `Jornada completa! Finalizada às ${status.expected_end}`;
+    case 'overtime':
+      return `Hora extra: +${formatMinutes(status.overtime_minutes)} desde as ${status.expected_end}`;
+  }
+};
+
 const theme = createTheme({
   palette: {
     mode: 'light',
@@ -187,7 +224,6 @@ function App() {
   });
   const [workStatus, setWorkStatus] = useState<WorkStatus | null>(null);
   const [store, setStore] = useState<Store | null>(null);
-  const [currentTime, setCurrentTime] = useState(new Date());
   const [notification, setNotification] = useState<{
     open: boolean;
     title: string;
@@ -209,25 +245,16 @@ function App() {
   const calculateProgress = () => {
     if (!workStatus) return 0;
     
-    // Parse total worked time
-    const totalWorkedMatch = workStatus.totalWorked.match(/(\d+)h\s*(\d+)m/);
-    if (!totalWorkedMatch) return 0;
-    
-    const hoursWorked = parseInt(totalWorkedMatch[1]);
-    const minutesWorked = parseInt(totalWorkedMatch[2]);
-    const totalMinutesWorked = hoursWorked * 60 + minutesWorked;
-    
-    const targetMinutes = 8 * 60; // 8 horas em minutos (480 minutos)
-    
-    // Se completou exatamente 8 horas ou mais, retorna 100%
-    if (totalMinutesWorked >= targetMinutes) {
+    // A fase vem do backend; completa só a partir da saída prevista
+    if (workStatus.phase === 'complete' || workStatus.phase === 'overtime') {
       return 100;
     }
+    if (workStatus.target_minutes <= 0) return 0;
     
-    // Caso contrário, calcula a porcentagem real sem arredondar para 100%
-    const progress = (totalMinutesWorked / targetMinutes) * 100;
+    // Tempo trabalhado, que não avança durante o almoço
+    const progress = (workStatus.worked_minutes / workStatus.target_minutes) * 100;
     
-    return progress;
+    return Math.min(Math.max(progress, 0), 100);
   };
 
   useEffect(() => {
@@ -267,8 +294,22 @@ function App() {
       console.log('Notification permission granted:', permissionGranted);
     };
 
+    // Status atual, caso o monitoramento já esteja em andamento (ex.: retomado ao abrir)
+    const initWorkStatus = async () => {
+      try {
+        const status = await invoke<WorkStatus | null>('get_status_snapshot');
+        if (status) {
+          setWorkStatus(status);
+          setIsMonitoring(true);
+        }
+      } catch (error) {
+        console.error('Error loading work status:', error);
+      }
+    };
+
     initStore();
     initNotifications();
+    initWorkStatus();
 
     // Setup event listeners for work completion
     const setupEventListeners = async () => {
@@ -291,22 +332,43 @@ function App() {
         });
       });
 
+      // Ações feitas pelo menu da bandeja
+      const unlisten3 = await listen<TimeData>('monitoring_started', (event) => {
+        setTimeData(event.payload);
+        setIsMonitoring(true);
+      });
+
+      const unlisten4 = await listen('monitoring_stopped', () => {
+        setIsMonitoring(false);
+        setWorkStatus(null);
+      });
+
+      const unlisten5 = await listen<TimeData>('time_data_updated', (event) => {
+        setTimeData(event.payload);
+      });
+
+      const unlisten6 = await listen('open_settings', () => {
+        setSettingsOpen(true);
+      });
+
+      const unlisten7 = await listen<WorkStatus | null>('work_status_updated', (event) => {
+        setWorkStatus(event.payload);
+      });
 
       return () => {
         unlisten1();
         unlisten2();
+        unlisten3();
+        unlisten4();
+        unlisten5();
+        unlisten6();
+        unlisten7();
       };
     };
 
     const cleanupListeners = setupEventListeners();
 
-    // Update current time every minute
-    const interval = setInterval(() => {
-      setCurrentTime(new Date());
-    }, 60000);
-
     return () => {
-      clearInterval(interval);
       cleanupListeners.then(cleanup => cleanup());
       // Remove context menu listener
       if (window.__TAURI__) {
@@ -315,69 +377,6 @@ function App() {
     };
   }, []);
 
-  useEffect(() => {
-    if (isMonitoring && timeData.inicio1 && timeData.fim1 && timeData.inicio2) {
-      calculateWorkStatus();
-    } else if (!isMonitoring) {
-      setWorkStatus(null);
-    }
-  }, [timeData, currentTime, isMonitoring]);
-
-  const calculateWorkStatus = () => {
-    try {
-      const today = format(new Date(), 'yyyy-MM-dd');
-      
-      const start1 = parse(`${today} ${timeData.inicio1}`, 'yyyy-MM-dd HH:mm', new Date());
-      const end1 = parse(`${today} ${timeData.fim1}`, 'yyyy-MM-dd HH:mm', new Date());
-      const start2 = parse(`${today} ${timeData.inicio2}`, 'yyyy-MM-dd HH:mm', new Date());
-      
-      // Calculate worked time in first period
-      const workedPeriod1 = differenceInMinutes(end1, start1);
-      
-      // Calculate worked time in second period (until now or until 8h total)
-      const now = new Date();
-      const totalTargetMinutes = 8 * 60; // 8 hours in minutes
-      
-      let workedPeriod2 = 0;
-      if (now > start2) {
-        workedPeriod2 = differenceInMinutes(now, start2);
-      }
-      
-      const totalWorked = workedPeriod1 + workedPeriod2;
-      const remainingMinutes = totalTargetMinutes - totalWorked;
-      
-      // Calculate end time
-      const endTime = addHours(start2, remainingMinutes / 60);
-      
-      setWorkStatus({
-        remainingTime: remainingMinutes > 0 ? `${Math.floor(remainingMinutes / 60)}h ${remainingMinutes % 60}m` : '0h 0m',
-        endTime: format(endTime, 'HH:mm'),
-        isComplete: remainingMinutes <= 0,
-        totalWorked: `${Math.floor(totalWorked / 60)}h ${totalWorked % 60}m`
-      });
-
-      // Show notifications based on remaining time
-      if (remainingMinutes <= 0 && !notification.open) {
-        setNotification({
-          open: true,
-          title: '🎉 Jornada Completa!',
-          message: 'Parabéns! Você completou suas 8 horas de trabalho. Tenha um ótimo resto do dia!',
-          type: 'success'
-        });
-        invoke('notify_work_complete').catch(console.error);
-      } else if (remainingMinutes <= 3 && remainingMinutes > 0 && !notification.open) {
-        setNotification({
-          open: true,
-          title: '⏰ Quase Acabando!',
-          message: `Faltam apenas ${Math.floor(remainingMinutes / 60)}h ${remainingMinutes % 60}m para completar sua jornada!`,
-          type: 'warning'
-        });
-      }
-    } catch (error) {
-      console.error('Error calculating work status:', error);
-    }
-  };
-
   const validateTimeInput = (value: string): string => {
     // Remove tudo que não é número
     let numbers = value.replace(/[^0-9]/g, '');
@@ -563,6 +562,50 @@ function App() {
     }
   };
 
+  const handleExportTimesheet = async () => {
+    const now = new Date();
+    const path = await save({
+      defaultPath: `espelho-de-ponto-${format(now, 'yyyy-MM')}.pdf`,
+      filters: [
+        { name: 'PDF', extensions: ['pdf'] },
+        { name: 'CSV', extensions: ['csv'] },
+        { name: 'JSON', extensions: ['json'] }
+      ]
+    });
+
+    if (!path) {
+      return;
+    }
+
+    // O formato segue a extensão escolhida no diálogo
+    const extension = path.split('.').pop()?.toLowerCase();
+    const exportFormat = extension === 'csv' || extension === 'json' ? extension : 'pdf';
+
+    try {
+      await invoke('export_timesheet', {
+        path,
+        startDate: format(startOfMonth(now), 'yyyy-MM-dd'),
+        endDate: format(endOfMonth(now), 'yyyy-MM-dd'),
+        format: exportFormat
+      });
+
+      setNotification({
+        open: true,
+        title: '✅ Espelho Exportado',
+        message: `Espelho de ponto do mês salvo em ${path}`,
+        type: 'success'
+      });
+    } catch (error) {
+      console.error('Error exporting timesheet:', error);
+      setNotification({
+        open: true,
+        title: '❌ Erro na Exportação',
+        message: typeof error === 'string' ? error : 'Erro ao exportar o espelho de ponto.',
+        type: 'error'
+      });
+    }
+  };
+
   return (
     <ThemeProvider theme={theme}>
       <CssBaseline />
@@ -768,6 +811,16 @@ function App() {
                   {isLoadingHours ? 'Buscando...' : 'Importar do PontoMais'}
                 </Button>
 
+                <Button
+                  variant="outlined"
+                  onClick={handleExportTimesheet}
+                  startIcon={<Description />}
+                  color="info"
+                  sx={{ minWidth: 160, width: '250px' }}
+                >
+                  Exportar Espelho do Mês
+                </Button>
+
                 <Button
                   variant="outlined"
                   onClick={handleTestNotification}
@@ -806,9 +859,7 @@ function App() {
                   backgroundColor: '#e2e8f0',
                   '& .MuiLinearProgress-bar': {
                     borderRadius: 6,
-                    background: calculateProgress() === 100 
-                      ? 'linear-gradient(90deg, #10b981 0%, #059669 100%)'
-                      : 'linear-gradient(90deg, #3b82f6 0%, #1d4ed8 100%)'
+                    background: phaseGradients[workStatus?.phase ?? 'working']
                   }
                 }}
               />
@@ -816,11 +867,13 @@ function App() {
               {workStatus && (
                 <Box sx={{ mt: 2, textAlign: 'center' }}>
                   <Typography variant="body2" color="text.secondary">
-                    {workStatus.isComplete 
-                      ? `Jornada completa! Finalizada às ${workStatus.endTime}`
-                      : `Faltam ${workStatus.remainingTime} para completar 8 horas`
-                    }
+                    {phaseDescription(workStatus)}
                   </Typography>
+                  {workStatus.next_reminder && (
+                    <Typography variant="body2" color="text.secondary">
+                      📅 Próximo compromisso às {workStatus.next_reminder.at}: {workStatus.next_reminder.title}
+                    </Typography>
+                  )}
                 </Box>
               )}
             </CardContent>
@@ -837,7 +890,7 @@ function App() {
                       color: '#3b82f6',
                       mb: 1
                     }}>
-                      {workStatus.totalWorked}
+                      {formatMinutes(workStatus.worked_minutes)}
                     </Typography>
                     <Typography variant="body1" color="text.secondary">
                       Tempo Trabalhado
@@ -851,13 +904,21 @@ function App() {
                   <CardContent sx={{ textAlign: 'center', py: 1.5 }}>
                     <Typography variant="h5" sx={{ 
                       fontWeight: 700, 
-                      color: workStatus.isComplete ? '#10b981' : '#f59e0b',
+                      color: phaseColors[workStatus.phase],
                       mb: 1
                     }}>
-                      {workStatus.isComplete ? workStatus.endTime : workStatus.remainingTime}
+                      {workStatus.phase === 'overtime'
+                        ? `+${formatMinutes(workStatus.overtime_minutes)}`
+                        : workStatus.phase === 'complete'
+                          ? workStatus.expected_end
+                          : formatMinutes(workStatus.remaining_minutes)}
                     </Typography>
                     <Typography variant="body1" color="text.secondary">
-                      {workStatus.isComplete ? "Finalizado às" : "Tempo Restante"}
+                      {workStatus.phase === 'overtime'
+                        ? "Hora Extra"
+                        : workStatus.phase === 'complete'
+                          ? "Finalizado às"
+                          : "Tempo Restante"}
                     </Typography>
                   </CardContent>
                 </Card>
@@ -907,12 +968,20 @@ function App() {
           {/* Alertas */}
           {workStatus && (
             <Alert 
-              severity={workStatus.isComplete ? "success" : "info"} 
+              severity={
+                workStatus.phase === 'overtime'
+                  ? "warning"
+                  : workStatus.phase === 'complete' ? "success" : "info"
+              } 
               sx={{ mt: 2 }}
             >
-              {workStatus.isComplete 
-                ? "🎉 Jornada de trabalho completa!" 
-                : "⏰ Monitorando jornada de trabalho..."
+              {workStatus.phase === 'overtime'
+                ? `⏱️ Em hora extra: +${formatMinutes(workStatus.overtime_minutes)}`
+                : workStatus.phase === 'complete'
+                  ? "🎉 Jornada de trabalho completa!"
+                  : workStatus.phase === 'final_minutes'
+                    ? "⏳ Últimos minutos da jornada!"
+                    : "⏰ Monitorando jornada de trabalho..."
               }
             </Alert>
           )}
@@ -13,17 +13,38 @@ import {
   IconButton,
   Card,
   CardContent,
-  Divider
+  Divider,
+  MenuItem,
+  FormControlLabel,
+  Switch
 } from '@mui/material';
 import { Visibility, VisibilityOff, Science, Save, Close } from '@mui/icons-material';
 import { invoke } from '@tauri-apps/api/core';
 
+interface ProviderInfo {
+  id: string;
+  name: string;
+}
+
+interface StartupSettings {
+  autostart: boolean;
+  startMinimized: boolean;
+  startupAction: 'none' | 'resume' | 'sync';
+}
+
+interface HttpApiSettings {
+  enabled: boolean;
+  port: number;
+  token: string;
+}
+
 interface SettingsProps {
   open: boolean;
   onClose: () => void;
 }
 
 interface PontoMaisConfig {
+  provider: string;
   employeeId: string;
   accessToken: string;
   client: string;
@@ -33,6 +54,7 @@ interface PontoMaisConfig {
 
 function Settings({ open, onClose }: SettingsProps) {
   const [config, setConfig] = useState<PontoMaisConfig>({
+    provider: 'pontomais',
     employeeId: '',
     accessToken: '',
     client: '',
@@ -40,6 +62,17 @@ function Settings({ open, onClose }: SettingsProps) {
     uuid: ''
   });
 
+  const [providers, setProviders] = useState<ProviderInfo[]>([]);
+  const [startup, setStartup] = useState<StartupSettings>({
+    autostart: false,
+    startMinimized: false,
+    startupAction: 'none'
+  });
+  const [httpApi, setHttpApi] = useState<HttpApiSettings>({
+    enabled: false,
+    port: 4848,
+    token: ''
+  });
   const [showTokens, setShowTokens] = useState(false);
   const [isSaving, setIsSaving] = useState(false);
   const [isTesting, setIsTesting] = useState(false);
@@ -56,16 +89,53 @@ function Settings({ open, onClose }: SettingsProps) {
 
   const loadConfig = async () => {
     try {
+      setProviders(await invoke<ProviderInfo[]>('list_time_card_providers'));
+
+      setStartup(await invoke<StartupSettings>('get_startup_settings'));
+      setHttpApi(await invoke<HttpApiSettings>('get_http_api_settings'));
+
       const savedConfig = await invoke<string>('get_pontomais_config');
       if (savedConfig) {
         const parsed = JSON.parse(savedConfig) as PontoMaisConfig;
-        setConfig(parsed);
+        // Configurações antigas não têm o campo provider
+        setConfig({ ...parsed, provider: parsed.provider || 'pontomais' });
       }
     } catch (error) {
       console.error('Erro ao carregar configurações:', error);
     }
   };
 
+  // Preferências de inicialização são aplicadas imediatamente
+  const handleStartupChange = async (changes: Partial<StartupSettings>) => {
+    const newStartup = { ...startup, ...changes };
+    setStartup(newStartup);
+    try {
+      await invoke('save_startup_settings', { settings: newStartup });
+    } catch (error) {
+      console.error('Erro ao salvar inicialização:', error);
+      setTestResult({
+        type: 'error',
+        message: typeof error === 'string' ? error : 'Erro ao configurar a inicialização automática!'
+      });
+    }
+  };
+
+  const handleHttpApiChange = async (enabled: boolean, regenerateToken = false) => {
+    try {
+      setHttpApi(await invoke<HttpApiSettings>('save_http_api_settings', {
+        enabled,
+        port: httpApi.port,
+        regenerateToken
+      }));
+    } catch (error) {
+      console.error('Erro ao configurar a API local:', error);
+      setTestResult({
+        type: 'error',
+        message: typeof error === 'string' ? error : 'Erro ao configurar a API local!'
+      });
+    }
+  };
+
   const handleSave = async () => {
     if (!config.employeeId || !config.accessToken || !config.client || !config.uid || !config.uuid) {
       setTestResult({
@@ -159,7 +229,7 @@ function Settings({ open, onClose }: SettingsProps) {
         pb: 1
       }}>
         <Typography variant="h6" component="div">
-          Configurações da API PontoMais
+          Configurações do Sistema de Ponto
         </Typography>
         <IconButton onClick={onClose} size="small">
           <Close />
@@ -176,6 +246,22 @@ function Settings({ open, onClose }: SettingsProps) {
               </Typography>
 
               <Stack spacing={2}>
+                <TextField
+                  select
+                  fullWidth
+                  label="Sistema de Ponto"
+                  value={config.provider}
+                  onChange={(e) => handleInputChange('provider', e.target.value)}
+                  variant="outlined"
+                  size="small"
+                >
+                  {providers.map((provider) => (
+                    <MenuItem key={provider.id} value={provider.id}>
+                      {provider.name}
+                    </MenuItem>
+                  ))}
+                </TextField>
+
                 <TextField
                   fullWidth
                   label="Employee ID"
@@ -246,6 +332,99 @@ function Settings({ open, onClose }: SettingsProps) {
             </CardContent>
           </Card>
 
+          <Card variant="outlined">
+            <CardContent sx={{ py: 2 }}>
+              <Typography variant="body2" fontWeight={500} sx={{ mb: 1 }}>
+                Inicialização
+              </Typography>
+
+              <Stack spacing={1}>
+                <FormControlLabel
+                  control={
+                    <Switch
+                      checked={startup.autostart}
+                      onChange={(e) => handleStartupChange({ autostart: e.target.checked })}
+                    />
+                  }
+                  label="Abrir o NoPonto ao iniciar a sessão"
+                />
+
+                <FormControlLabel
+                  control={
+                    <Switch
+                      checked={startup.startMinimized}
+                      disabled={!startup.autostart}
+                      onChange={(e) => handleStartupChange({ startMinimized: e.target.checked })}
+                    />
+                  }
+                  label="Iniciar minimizado na bandeja"
+                />
+
+                <TextField
+                  select
+                  fullWidth
+                  label="Ao abrir"
+                  value={startup.startupAction}
+                  onChange={(e) => handleStartupChange({ startupAction: e.target.value as StartupSettings['startupAction'] })}
+                  variant="outlined"
+                  size="small"
+                >
+                  <MenuItem value="none">Não fazer nada</MenuItem>
+                  <MenuItem value="resume">Retomar o monitoramento de hoje</MenuItem>
+                  <MenuItem value="sync">Sincronizar com o sistema de ponto</MenuItem>
+                </TextField>
+              </Stack>
+            </CardContent>
+          </Card>
+
+          <Card variant="outlined">
+            <CardContent sx={{ py: 2 }}>
+              <Typography variant="body2" fontWeight={500} sx={{ mb: 1 }}>
+                API Local
+              </Typography>
+
+              <Stack spacing={2}>
+                <FormControlLabel
+                  control={
+                    <Switch
+                      checked={httpApi.enabled}
+                      onChange={(e) => handleHttpApiChange(e.target.checked)}
+                    />
+                  }
+                  label="Permitir acesso por scripts e plugins (somente localhost)"
+                />
+
+                <TextField
+                  fullWidth
+                  label="Porta"
+                  type="number"
+                  value={httpApi.port}
+                  onChange={(e) => setHttpApi({ ...httpApi, port: Number(e.target.value) })}
+                  onBlur={() => httpApi.enabled && handleHttpApiChange(true)}
+                  variant="outlined"
+                  size="small"
+                />
+
+                {httpApi.token && (
+                  <Box sx={{ display: 'flex', gap: 1, alignItems: 'center' }}>
+                    <TextField
+                      fullWidth
+                      label="Token"
+                      type={showTokens ? 'text' : 'password'}
+                      value={httpApi.token}
+                      variant="outlined"
+                      size="small"
+                      InputProps={{ readOnly: true }}
+                    />
+                    <Button size="small" onClick={() => handleHttpApiChange(httpApi.enabled, true)}>
+                      Gerar novo
+                    </Button>
+                  </Box>
+                )}
+              </Stack>
+            </CardContent>
+          </Card>
+
           {testResult.type && (
             <Alert severity={testResult.type}>
               {testResult.message}